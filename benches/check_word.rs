@@ -0,0 +1,23 @@
+// Benchmarks for check_word(): a short valid word, a long valid compound,
+// and a long invalid token made of short morphemes, which causes backtracking.
+// Run with: cargo bench
+// Klivo 2020-05-28
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use literumilo::*;
+
+fn bench_check_word(c: &mut Criterion) {
+    let dictionary = load_precompiled().unwrap();
+    c.bench_function("short valid word", |b| {
+        b.iter(|| check_word(black_box("ĉiutage"), &dictionary))
+    });
+    c.bench_function("long valid compound", |b| {
+        b.iter(|| check_word(black_box("malsanulejestrinoj"), &dictionary))
+    });
+    c.bench_function("long invalid token", |b| {
+        b.iter(|| check_word(black_box("ararararararararararararararararararararararararo"), &dictionary))
+    });
+}
+
+criterion_group!(benches, bench_check_word);
+criterion_main!(benches);
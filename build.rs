@@ -0,0 +1,119 @@
+// build.rs - Precompiles the dictionary (src/vortaro.tsv) into a compact binary file,
+// which is embedded in the library and loaded by load_precompiled(). This avoids
+// parsing the tab-separated data every time the program starts.
+//
+// The format (see load_precompiled() in vortaro.rs):
+//     magic 'LTRM', format version (1 byte)
+//     string table: count (1 byte), then each string as length (1 byte) + bytes
+//     entry count (4 bytes, little endian)
+//     each entry: key, word (length + bytes), 7 string table indexes
+//         (part of speech, meaning, transitivity, without ending, with ending,
+//         synthesis, flag) and rarity (1 byte each)
+//
+// Klivo 2020-05-27
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+// The entries, their codes and make_key() are shared with the library, so that
+// unknown codes are caught when the library is built, and the keys are the same.
+#[macro_use]
+#[path = "src/macros.rs"]
+mod macros;
+#[path = "src/x_system.rs"]
+mod x_system;
+#[allow(dead_code, clippy::upper_case_acronyms)]
+#[path = "src/entry.rs"]
+mod entry;
+
+use x_system::x_to_accent;
+use entry::*;
+
+// Checks that each column of a row has a code which the parse() functions
+// of entry.rs accept, eg. "VERBO" for POS::Verb.
+fn unknown_code(fields: &[&str]) -> Option<(&'static str, String)> {
+    let known = [
+        ("part of speech", fields[1], POS::parse(fields[1]).is_some()),
+        ("meaning", fields[2], Meaning::parse(fields[2]).is_some()),
+        ("transitivity", fields[3], Transitivity::parse(fields[3]).is_some()),
+        ("without-ending", fields[4], WithoutEnding::parse(fields[4]).is_some()),
+        ("with-ending", fields[5], WithEnding::parse(fields[5]).is_some()),
+        ("synthesis", fields[6], Synthesis::parse(fields[6]).is_some()),
+        ("flag", fields[8], Flag::parse(fields[8]).is_some()),
+    ];
+    for (column, code, ok) in known.iter() {
+        if !ok { return Some((column, String::from(*code))); }
+    }
+    return None;
+}
+
+fn push_string(out: &mut Vec<u8>, s: &str) {
+    assert!(s.len() < 256, "String too long for the precompiled dictionary: {}", s);
+    out.push(s.len() as u8);
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn main() {
+
+    println!("cargo:rerun-if-changed=src/vortaro.tsv");
+    println!("cargo:rerun-if-changed=src/entry.rs");
+    println!("cargo:rerun-if-changed=src/x_system.rs");
+    println!("cargo:rerun-if-changed=src/macros.rs");
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let data = fs::read_to_string("src/vortaro.tsv").expect("Could not read src/vortaro.tsv");
+
+    let mut table: Vec<String> = vec![];
+    let mut entries: Vec<u8> = vec![];
+    let mut count: u32 = 0;
+
+    for (index, line) in data.lines().enumerate() {
+
+        if line.trim().is_empty() || line.starts_with('#') { continue; }
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 9 {
+            panic!("vortaro.tsv, line {}: expected 9 fields, found {}", index + 1, fields.len());
+        }
+        let rarity: u8 = match fields[7].parse() {
+            Ok(rarity) => rarity,
+            Err(_) => panic!("vortaro.tsv, line {}: invalid rarity '{}'", index + 1, fields[7]),
+        };
+        if let Some((column, code)) = unknown_code(&fields) {
+            panic!("vortaro.tsv, line {}: unknown {} code '{}'", index + 1, column, code);
+        }
+
+        // As in make_dictionary(), exclusions and single letters are skipped.
+        let entry = match Entry::new(fields.clone()) {
+            Some(entry) => entry,
+            None => continue,
+        };
+        let word = entry.word;
+        let key = make_key(fields[0], entry.part_of_speech);
+
+        push_string(&mut entries, &key);
+        push_string(&mut entries, &word);
+        for &i in [1, 2, 3, 4, 5, 6, 8].iter() {
+            let position = match table.iter().position(|s| s == fields[i]) {
+                Some(position) => position,
+                None => { table.push(String::from(fields[i])); table.len() - 1 },
+            };
+            entries.push(position as u8);
+        }
+        entries.push(rarity);
+        count += 1;
+    }
+
+    assert!(table.len() < 256, "Too many distinct codes in vortaro.tsv.");
+
+    let mut out: Vec<u8> = b"LTRM".to_vec();
+    out.push(1);
+    out.push(table.len() as u8);
+    for s in &table { push_string(&mut out, s); }
+    out.extend_from_slice(&count.to_le_bytes());
+    out.extend_from_slice(&entries);
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is not set");
+    fs::write(Path::new(&out_dir).join("vortaro.bin"), out).expect("Could not write vortaro.bin");
+}
@@ -5,8 +5,9 @@
 #![allow(dead_code)]
 
 use std::collections::HashMap;
+use std::sync::OnceLock;
 
-use literumilo::*;
+use super::*;
 
 use super::suffix::*;
 use super::entry::*;
@@ -14,17 +15,108 @@ use super::ending::*;
 use super::morpheme_list::*;
 use super::scan_morphemes::*;
 
-const  MAX_MORPHEMES: usize = 9; // The maximum number of morphemes in a compound word.
+/*
+ * Options
+ * Settings which control the analysis of words.
+ * Fields:
+ *     'max_morphemes' is the maximum number of morphemes in a compound word.
+ *     'max_separators' is the maximum number of separator vowels, eg. 'fingr.o.montr.i'.
+ *     'strict_caps' rejects names which are written in lower case, eg. 'usono'.
+ *     (See check_capitals().)
+ *     'exceptions' replaces the map of words which are divided from a table.
+ *     None means the default map. (See default_exceptions().)
+ */
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub max_morphemes: usize,
+    pub max_separators: u32,
+    pub strict_caps: bool,
+    pub exceptions: Option<HashMap<String, String>>,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        return Options { max_morphemes: MAX_MORPHEMES, max_separators: 1,
+                         strict_caps: false, exceptions: None };
+    }
+}
+
+/*
+ * MorphemeInfo
+ * Describes one morpheme of an analyzed word.
+ * Fields:
+ *     'word' is the morpheme, eg. 'dir'.
+ *     'part_of_speech', 'meaning', 'transitivity', 'synthesis' and 'rarity' are taken
+ *     from the dictionary entry. (Rarity: 0 is common, 4 is rare.)
+ *     'is_separator' is true for a separator vowel, eg. the 'o' of 'nask.o.tag.o',
+ *     which joins two roots, but is not a morpheme itself.
+ */
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MorphemeInfo {
+    pub word: String,
+    pub part_of_speech: POS,
+    pub meaning: Meaning,
+    pub transitivity: Transitivity,
+    pub synthesis: Synthesis,
+    pub rarity: usize,
+    pub is_separator: bool,
+}
+
+impl MorphemeInfo {
+    fn new(entry: &Entry) -> MorphemeInfo {
+        return MorphemeInfo {
+            word: entry.word.clone(),
+            part_of_speech: entry.part_of_speech,
+            meaning: entry.meaning,
+            transitivity: entry.transitivity,
+            synthesis: entry.synthesis,
+            rarity: entry.rarity,
+            is_separator: entry.flag == Flag::Separator,
+        };
+    }
+}
+
+/*
+ * Invalidity
+ * The reason why a word is invalid.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Invalidity {
+    NoEnding,		// The word has no valid grammatical ending.
+    RootNotFound,	// The word could not be divided into known morphemes.
+    BadSynthesis { index: usize },	// The morpheme at 'index' does not combine validly.
+    TooManyMorphemes,	// The word has more than the maximum number of morphemes.
+    Uncapitalized,	// A name, eg. 'usono', is written in lower case. (Options::strict_caps)
+                    // Also an abbreviation in capitals, eg. 'uea'. (See check_abbreviation_caps().)
+}
 
 /*
  * AnalysisResult
  * Fields:
  *     'word' has the original word divided into morphemes, eg. 'mis.dir.it.a'.
  *     'valid' is true if the word is a valid Esperanto word. (correctly spelled)
+ *     'morphemes' describes each morpheme of a valid word. Empty if invalid.
+ *     'part_of_speech' is the part of speech indicated by the grammatical ending.
+ *     'ending' is the grammatical ending, eg. 'ojn'. None for words without an ending.
+ *     'reason' explains why a word is invalid. None if valid.
+ *     'accusative' is true if the word has an accusative ending (-n). For adverbs,
+ *     such as 'hejm.en', this indicates direction rather than a direct object.
+ *     'rarity' is the rarity of the rarest morpheme (0 is common, 4 is rare).
+ *     None if the word has no morphemes from the dictionary, eg. 'kiu', '3-a'.
  */
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AnalysisResult {
     pub word: String,
     pub valid: bool,
+    pub morphemes: Vec<MorphemeInfo>,
+    pub part_of_speech: Option<POS>,
+    pub ending: Option<String>,
+    pub reason: Option<Invalidity>,
+    pub accusative: bool,
+    pub rarity: Option<usize>,
 }
 
 impl AnalysisResult {
@@ -39,9 +131,195 @@ impl AnalysisResult {
      * Returns:
      *    analysis result
      */
-    fn new(original: &str, word: &str, valid: bool) -> AnalysisResult {
+    pub(crate) fn new(original: &str, word: &str, valid: bool) -> AnalysisResult {
         let word2 = restore_capitals(original, word);
-        return AnalysisResult { word: word2, valid: valid };
+        return AnalysisResult::unchanged(&word2, valid);
+    }
+
+    /*
+     * unchanged()
+     *
+     * Makes an analysis result which keeps the original word as it is. It is
+     * not divided into morphemes, and its capitals are not restored.
+     *
+     * Params:
+     *    original word
+     *    valid  (true or false)
+     * Returns:
+     *    analysis result
+     */
+    fn unchanged(original: &str, valid: bool) -> AnalysisResult {
+        return AnalysisResult {
+            word: String::from(original),
+            valid,
+            morphemes: vec![],
+            part_of_speech: None,
+            ending: None,
+            reason: None,
+            accusative: false,
+            rarity: None,
+        };
+    }
+
+    /*
+     * invalid()
+     *
+     * Makes an analysis result for an invalid word.
+     *
+     * Params:
+     *    original word
+     *    word
+     *    reason for invalidity
+     * Returns:
+     *    analysis result
+     */
+    fn invalid(original: &str, word: &str, reason: Invalidity) -> AnalysisResult {
+        let mut result = AnalysisResult::new(original, word, false);
+        result.reason = Some(reason);
+        return result;
+    }
+
+    /*
+     * set_ending()
+     *
+     * Records the grammatical ending of the word, and the part of speech it indicates.
+     *
+     * Params:
+     *    ending
+     */
+    fn set_ending(&mut self, ending: &Ending) {
+        self.part_of_speech = Some(ending.pos);
+        self.ending = Some(String::from(ending.ending));
+        self.accusative = ending.is_accusative();
+    }
+
+    /*
+     * push_morpheme()
+     *
+     * Adds a morpheme to the analysis, and updates the rarity of the word.
+     * Separators (eg. the 'o' of 'fingr.o.montr.i') do not affect the rarity.
+     *
+     * Params:
+     *    entry of the morpheme
+     */
+    fn push_morpheme(&mut self, entry: &Entry) {
+        if entry.flag != Flag::Separator {
+            self.rarity = Some(self.rarity.map_or(entry.rarity, |r| r.max(entry.rarity)));
+        }
+        self.morphemes.push(MorphemeInfo::new(entry));
+    }
+
+    /*
+     * iter()
+     *
+     * Iterates over the morphemes of a valid word, in order, so that a caller can
+     * inspect each one without dividing the string. Separator vowels are included,
+     * and marked by 'is_separator'. The grammatical ending is not a morpheme.
+     *
+     * Returns:
+     *    iterator over the morphemes (MorphemeInfo)
+     */
+    /// ```
+    /// let dictionary = literumilo::load_precompiled().unwrap();
+    /// let result = literumilo::check_word("forigitaj", &dictionary);
+    /// let morphemes: Vec<&str> = result.iter().map(|m| m.word.as_str()).collect();
+    /// assert_eq!(morphemes, vec!["for", "ig", "it"]);
+    /// assert_eq!(result.ending.as_deref(), Some("aj"));
+    /// ```
+    pub fn iter(&self) -> std::slice::Iter<'_, MorphemeInfo> {
+        return self.morphemes.iter();
+    }
+
+    /*
+     * with_entry()
+     *
+     * Makes a valid analysis result for a word which resolves to a single morpheme.
+     *
+     * Params:
+     *    original word
+     *    word  (divided into morphemes)
+     *    dictionary entry
+     * Returns:
+     *    analysis result
+     */
+    fn with_entry(original: &str, word: &str, entry: &Entry) -> AnalysisResult {
+        let mut result = AnalysisResult::new(original, word, true);
+        result.push_morpheme(entry);
+        return result;
+    }
+
+    /*
+     * display_form()
+     *
+     * Returns the word divided into morphemes. If 'mark_separators' is true,
+     * separator vowels are put between parentheses, eg. 'nask.(o).tag.o', so
+     * that they can be distinguished from roots.
+     *
+     * Params:
+     *    mark_separators
+     * Returns:
+     *    divided word, eg. 'nask.o.tag.o'
+     */
+    pub fn display_form(&self, mark_separators: bool) -> String {
+        if !mark_separators || !self.morphemes.iter().any(|m| m.is_separator) {
+            return self.word.clone();
+        }
+        let (mut display, rest) = self.format_morphemes(|display, _, morpheme, text| {
+            if morpheme.is_separator {
+                display.push('(');
+                display.push_str(text);
+                display.push(')');
+            }
+            else { display.push_str(text); }
+        });
+        display.push_str(&rest);
+        return display;
+    }
+
+    /*
+     * format_morphemes()
+     *
+     * Walks through the divided word, and calls 'format' to append each morpheme
+     * to the output. The periods and hyphens between morphemes are copied as they
+     * are. Used by display_form() and tagged_form().
+     *
+     * Params:
+     *    format - appends a morpheme to the output. Its arguments are the output,
+     *             the index of the morpheme, its description and its text.
+     * Returns:
+     *    formatted morphemes, and the rest of the word (the ending), eg. 'aj'
+     */
+    pub(crate) fn format_morphemes<F>(&self, mut format: F) -> (String, String)
+        where F: FnMut(&mut String, usize, &MorphemeInfo, &str) {
+        let mut chars = self.word.chars().peekable();
+        let mut output = String::new();
+        for (index, morpheme) in self.morphemes.iter().enumerate() {
+            push_separators(&mut chars, &mut output);
+            // Compound entries, eg. 'muzik.il', keep their periods.
+            let mut letters = morpheme.word.chars().filter(|&ch| ch != '.').count();
+            let mut text = String::new();
+            while letters > 0 {
+                match chars.next() {
+                    Some(ch) => {
+                        if ch != '.' && !is_hyphen!(ch) { letters -= 1; }
+                        text.push(ch);
+                    },
+                    None => break,
+                }
+            }
+            format(&mut output, index, morpheme, &text);
+        }
+        push_separators(&mut chars, &mut output);
+        return (output, chars.collect());
+    }
+}
+
+// Moves the periods and hyphens between morphemes from 'chars' to 'output'.
+fn push_separators(chars: &mut std::iter::Peekable<std::str::Chars>, output: &mut String) {
+    while let Some(&ch) = chars.peek() {
+        if ch != '.' && !is_hyphen!(ch) { break; }
+        output.push(ch);
+        chars.next();
     }
 }
 
@@ -59,11 +337,13 @@ impl AnalysisResult {
  *      index of morpheme (int)
  *      list of morphemes
  *      last_morpheme (t/f)
+ *      options
  * Return:
  *      true if valid, false otherwise
  */
-fn check_synthesis(rest_of_word: &str, dictionary: &HashMap<String, Entry>, index: usize,
-                            morpheme_list: &mut Morphemes<'_>, last_morpheme: bool) -> bool {
+fn check_synthesis<'a>(rest_of_word: &str, dictionary: &'a Dictionary, index: usize,
+                            morpheme_list: &mut Morphemes<'a>, last_morpheme: bool,
+                            options: &Options) -> bool {
     let syn;
     let word;
 
@@ -73,18 +353,36 @@ fn check_synthesis(rest_of_word: &str, dictionary: &HashMap<String, Entry>, inde
     }
     else { return false; }
 
-    if syn == Synthesis::Suffix && !check_suffix(&word, index, morpheme_list) { return false; }
+    if syn == Synthesis::Suffix && !check_suffix(&word, index, morpheme_list) {
+        return morpheme_list.fail(Invalidity::BadSynthesis { index });
+    }
+
+    // A participle takes the transitivity of its verb, for the suffixes which follow it.
+    if syn == Synthesis::Participle { inherit_transitivity(index, morpheme_list); }
 
     if !last_morpheme {
         // Divide the rest of the word into morphemes.
-        if !find_morpheme(rest_of_word, dictionary, index + 1, morpheme_list) { return false; }
+        if !find_morpheme(rest_of_word, dictionary, index + 1, morpheme_list, options) {
+            return false;
+        }
         return true;
     }
 
     if last_morpheme {
         // Check prefixes (and limited morphemes) after the word has been divided,
         // because the validity of a prefix depends on the morphemes which come after it.
-        return scan_morphemes(morpheme_list);
+        let valid = scan_morphemes(morpheme_list, options);
+        // When every division is wanted, record this one, and keep searching.
+        if valid && morpheme_list.collecting() {
+            morpheme_list.record_division();
+            return false;
+        }
+        // When a division with fewer morphemes is wanted, record this one, and keep
+        // searching, unless no division can have fewer morphemes.
+        if valid && morpheme_list.seeking_fewer() {
+            return morpheme_list.record_fewer();
+        }
+        return valid;
     }
 
     return false;
@@ -92,50 +390,190 @@ fn check_synthesis(rest_of_word: &str, dictionary: &HashMap<String, Entry>, inde
 }  // check_synthesis
 
 
+/*
+ * joins_others
+ *
+ * Determines whether a dictionary entry can be a morpheme of a compound word.
+ * Entries whose synthesis is 'No' cannot join other morphemes. Entries which are
+ * themselves compounds (Flag::Compound, eg. 'muzik.il') are accepted as whole words,
+ * and at the beginning or end of a compound (eg. 'fanfaron.eg.ec.o'), but not in
+ * its interior, to avoid double derivations. Their own morphemes (eg. 'muzik' and
+ * 'il') can still be combined there.
+ *
+ * Params:
+ *    entry
+ *    interior - true if other morphemes precede and follow the entry
+ * Return:
+ *    true if the entry can be joined with other morphemes
+ */
+fn joins_others(entry: &Entry, interior: bool) -> bool {
+    if interior && entry.flag == Flag::Compound { return false; }
+    return entry.synthesis != Synthesis::No;
+}
+
+
+/*
+ * min_morphemes
+ *
+ * For each rest of a word (what remains after the first morphemes have been found),
+ * this function calculates the minimum number of morphemes into which it could be
+ * divided, ignoring the rules of synthesis. The pieces are dictionary entries which
+ * can be combined, separators (o, a, e) and the country suffix (i). find_morpheme()
+ * uses these counts to abandon a division which cannot succeed, rather than
+ * exploring the same rest of the word again and again while backtracking.
+ * Because the counts take time to calculate, they are only used for words which
+ * need many steps (see PRUNE_AFTER_STEPS), that is, long invalid words.
+ *
+ * Params:
+ *    word - the word to divide (without its grammatical ending)
+ *    dictionary - a map of word data
+ * Return:
+ *    minimum counts, indexed by the length in bytes of the rest of the word
+ *    (None if the rest cannot be divided)
+ */
+fn min_morphemes(word: &str, dictionary: &Dictionary) -> Vec<Option<usize>> {
+
+    let mut counts: Vec<Option<usize>> = vec![None; word.len() + 1];
+    counts[0] = Some(0);
+
+    let starts: Vec<usize> = word.char_indices().map(|(offset, _)| offset).collect();
+    for &start in starts.iter().rev() {
+        let rest = &word[start..];
+        let mut best: Option<usize> = None;
+        let mut consider = |remaining: usize| {
+            if let Some(count) = counts[remaining] {
+                if best.is_none_or(|b| count + 1 < b) { best = Some(count + 1); }
+            }
+        };
+        for (bytes, size) in dictionary.prefixes(rest) {
+            if size < 2 { continue; }
+            if let Some(entry) = dictionary.get(&rest[..bytes]) {
+                if joins_others(entry, false) { consider(rest.len() - bytes); }
+            }
+        }
+        if rest.starts_with(['o', 'a', 'e']) {
+            consider(rest.len() - 1);
+        }
+        if rest == "i" { consider(0); }
+        counts[rest.len()] = best;
+    }
+    return counts;
+
+}  // min_morphemes
+
+
 /*
  * find_morpheme - trovu_radikon
  *
  * This function divides a (presumably) compound word into morphemes, while checking synthesis.
- * It is recursive.
+ * It is recursive. The depth of recursion is limited by the maximum number of morphemes,
+ * and the total number of calls for one word is limited by MAX_STEPS, so that a long
+ * invalid token (eg. from OCR) is rejected quickly. Real words need fewer than 50 steps.
  *
  * Params:
  *    rest_of_word - the remainder to be analyzed
  *    dictionary - a map of word data
  *    index of morpheme (indekso de radiko)
  *    morpheme_list - holds a vector of previously collected morphemes
+ *    options - settings for analysis
  * Return:
  *    true for valid synthesis, false for invalid.
  */
-fn find_morpheme(rest_of_word: &str, dictionary: &HashMap<String, Entry>,
-                            index: usize, morpheme_list: &mut Morphemes) -> bool {
+fn find_morpheme<'a>(rest_of_word: &str, dictionary: &'a Dictionary,
+                            index: usize, morpheme_list: &mut Morphemes<'a>,
+                            options: &Options) -> bool {
 
-    if index >= MAX_MORPHEMES { return false; }
+    if index >= options.max_morphemes { return morpheme_list.fail(Invalidity::TooManyMorphemes); }
 
-    if index > 0 {
-        if let Some(entry) = dictionary.get(rest_of_word) {
-            // Do we allow this morpheme to join with others?
-            if entry.synthesis != Synthesis::No {
-                morpheme_list.put(index, entry);
-                //println!("Rest of word: {}", rest_of_word);
-                let valid = check_synthesis(&rest_of_word, dictionary, index, morpheme_list, true);
-                if valid { return true; }
-            }
+    // Bound the backtracking for long invalid words. (See MAX_STEPS.)
+    if !morpheme_list.step() { return morpheme_list.fail(Invalidity::RootNotFound); }
+
+    // A word which needs many steps is probably invalid. Abandon this division if
+    // the rest of the word cannot be divided into the morphemes which remain.
+    // (See min_morphemes().)
+    if index == 0 { morpheme_list.set_word(rest_of_word); }
+    if morpheme_list.steps() > PRUNE_AFTER_STEPS {
+        if !morpheme_list.has_min_morphemes() {
+            let counts = min_morphemes(morpheme_list.word(), dictionary);
+            morpheme_list.set_min_morphemes(counts);
+        }
+        match morpheme_list.min_morphemes(rest_of_word.len()) {
+            None => return morpheme_list.fail(Invalidity::RootNotFound),
+            Some(count) if index + count > options.max_morphemes => {
+                return morpheme_list.fail(Invalidity::TooManyMorphemes);
+            },
+            _ => (),
+        }
+    }
+
+    // After a valid division has been found, only a division with fewer morphemes
+    // is wanted, and the rest of the word needs at least one. (See seek_fewer().)
+    if let Some(fewest) = morpheme_list.fewest() {
+        if index + 1 >= fewest { return false; }
+    }
+
+    if let Some(entry) = dictionary.get(rest_of_word) {
+        // A prefix can't be the last morpheme, eg. 're' in 'mal.re.a', or the only
+        // one, as in 're.a'. (scan_morphemes() would reject it, after the division
+        // is complete.)
+        if entry.synthesis == Synthesis::Prefix {
+            morpheme_list.put(index, entry);
+            morpheme_list.record_progress(index, morpheme_list.word().len());
+            morpheme_list.fail(Invalidity::BadSynthesis { index });
+        }
+        // Do we allow this morpheme to join with others?
+        else if index > 0 && joins_others(entry, false) {
+            morpheme_list.put(index, entry);
+            morpheme_list.record_progress(index, morpheme_list.word().len());
+            //println!("Rest of word: {}", rest_of_word);
+            let valid = check_synthesis(rest_of_word, dictionary, index, morpheme_list, true, options);
+            if valid { return true; }
         }
     }
 
-    let length_of_word = rest_of_word.chars().count();
+    // With only one morpheme left to find, the rest of the word must be one entry.
+    if morpheme_list.fewest().is_some_and(|fewest| index + 2 >= fewest) { return false; }
+
+    // Byte offsets of the character boundaries, so that the word can be sliced
+    // by characters without collecting new strings. The last is the length in bytes.
+    let boundaries: Vec<usize> = rest_of_word.char_indices().map(|(offset, _)| offset)
+                                 .chain(std::iter::once(rest_of_word.len())).collect();
+    let length_of_word = boundaries.len() - 1;
     let min_length = 2;  // minimum length of a morpheme
+
+    // The country suffix -io, eg. 'Finn.i.o'. It has only one letter, so it is
+    // not in the dictionary, and the loop below does not find it.
+    if morpheme_list.type_of_ending() == POS::Substantive {
+        if index > 0 && rest_of_word == "i" {
+            morpheme_list.put_owned(index, Entry::new_country_suffix());
+            let valid = check_synthesis(rest_of_word, dictionary, index, morpheme_list, true, options);
+            if valid { return true; }
+        }
+        else if length_of_word > min_length && rest_of_word.ends_with('i') {
+            let root = &rest_of_word[..boundaries[length_of_word - 1]];
+            if let Some(entry) = dictionary.get(root) {
+                if joins_others(entry, index > 0) {
+                    morpheme_list.put(index, entry);
+                    morpheme_list.record_progress(index, morpheme_list.word().len() - 1);
+                    let valid = check_synthesis("i", dictionary, index, morpheme_list, false, options);
+                    if valid { return true; }
+                }
+            }
+        }
+    }
     let max_length = length_of_word - 1;
 
     // Try to find a valid morpheme, by dividing the rest of the word.
-    for size in (min_length .. max_length).rev() {
-        let morpheme: String = rest_of_word.chars().take(size).collect();
-        if let Some(entry) = dictionary.get(&morpheme) {
+    // The trie gives the morphemes which begin the rest of the word. Longest first.
+    for (_, size) in dictionary.prefixes(rest_of_word).into_iter().rev() {
+        if size < min_length || size >= max_length { continue; }
+        if let Some(entry) = dictionary.get(&rest_of_word[..boundaries[size]]) {
             // Do we allow this morpheme to join with others?
-            if entry.synthesis != Synthesis::No {
-                let rest_of_word: String = rest_of_word.chars().skip(size).collect();
+            if joins_others(entry, index > 0) {
+                let rest_of_word = &rest_of_word[boundaries[size]..];
                 morpheme_list.put(index, entry);
-                let valid = check_synthesis(&rest_of_word, dictionary, index, morpheme_list, false);
+                morpheme_list.record_progress(index, morpheme_list.word().len() - rest_of_word.len());
+                let valid = check_synthesis(rest_of_word, dictionary, index, morpheme_list, false, options);
                 if valid {
                     return true;
                }
@@ -146,15 +584,16 @@ fn find_morpheme(rest_of_word: &str, dictionary: &HashMap<String, Entry>,
     // Sometimes there is a separator (a grammatical ending) between morphemes.
     // This is usually done to aid pronunciation. Instead of 'fingr.montri.', most would
     // write 'fingr.o.montr.i'. Other examples are: ĝust.a.temp.e, unu.a.foj.e, etc.
-    // This algorithm will accept one separator per word. It must be 'o', 'a' or 'e'.
+    // By default, this algorithm will accept one separator per word. (See Options.)
+    // It must be 'o', 'a' or 'e'.
 
     if index == 0 || length_of_word < 3 { return false; }
 
-    let separator: String = rest_of_word.chars().take(1).collect();
-    if let Some(entry) = Entry::new_separator(&separator) {
-        morpheme_list.put(index, &entry);
-        let rest_of_word: String = rest_of_word.chars().skip(1).collect();
-        let valid = check_synthesis(&rest_of_word, dictionary, index, morpheme_list, false);
+    let separator = &rest_of_word[..boundaries[1]];
+    if let Some(entry) = Entry::new_separator(separator) {
+        morpheme_list.put_owned(index, entry);
+        let rest_of_word = &rest_of_word[boundaries[1]..];
+        let valid = check_synthesis(rest_of_word, dictionary, index, morpheme_list, false, options);
         if valid { return true; }
     }
 
@@ -164,115 +603,1331 @@ fn find_morpheme(rest_of_word: &str, dictionary: &HashMap<String, Entry>,
 
 
 /*
- * check_word
+ * check_correlative
  *
- * This function tests whether a word is correctly spelled.
+ * Recognizes the table of correlatives (tabelvortoj), such as 'kiu', 'tiam',
+ * 'ĉies' and 'nenial'. A correlative consists of one of the beginnings ki-, ti-,
+ * i-, ĉi- or neni-, and one of the endings -u, -o, -a, -e, -al, -am, -el, -es, -om.
+ * Correlatives ending in -u and -a can take the plural -j and the accusative -n.
+ * Those ending in -o and -e can take -n. Eg. 'tiu.jn', 'kio.n', 'tie.n'.
  *
  * Params:
- *    word - the word to test
- *    dictionary - a map of word data
+ *    original word
  * Return:
- *     AnalysisResult
+ *    optional analysis result
  */
-pub fn check_word(original_word: &str, dictionary: &HashMap<String, Entry>) -> AnalysisResult {
+fn check_correlative(original_word: &str) -> Option<AnalysisResult> {
 
-    let length_of_word = original_word.chars().count();
+    let word = original_word.to_lowercase();
 
-    if length_of_word == 1 {   // Single letters are OK.
-        let chrs:Vec<char> = original_word.chars().collect();
-        if is_word_char!(chrs[0]) {
-            return AnalysisResult::new(original_word, original_word, true);
+    let mut rest = "";
+    for beginning in ["neni", "ĉi", "ki", "ti", "i"].iter() {
+        if let Some(r) = word.strip_prefix(beginning) {
+            rest = r;
+            break;
+        }
+    }
+    if rest.is_empty() { return None; }
+
+    for ending in ["al", "am", "el", "es", "om", "u", "o", "a", "e"].iter() {
+        let suffix = match rest.strip_prefix(ending) {
+            Some(suffix) => suffix,
+            None => continue,
+        };
+        let valid_suffix = matches!((*ending, suffix),
+            (_, "") |
+            ("u", "j") | ("u", "n") | ("u", "jn") |
+            ("a", "j") | ("a", "n") | ("a", "jn") |
+            ("o", "n") | ("e", "n"));
+        if !valid_suffix { return None; }
+
+        let length = word.len() - suffix.len();
+        let pos = match *ending {
+            "u" | "a" => POS::PronounAdjective,
+            "o" | "es" => POS::Pronoun,
+            _ => POS::Adverb,
+        };
+        let mut result;
+        if !suffix.is_empty() {
+            let w = format!("{}.{}", &word[..length], suffix);
+            result = AnalysisResult::new(original_word, &w, true);
+            result.ending = Some(String::from(suffix));
+            result.accusative = suffix.ends_with('n');
         }
         else {
-            return AnalysisResult::new(original_word, original_word, false);
+            result = AnalysisResult::new(original_word, &word, true);
         }
+        result.part_of_speech = Some(pos);
+        return Some(result);
     }
+    return None;
 
-    // Check for abbreviations, such as n-r.oj, s-in.oj
-    // The second character must be a hyphen.
-    if length_of_word > 2 {
-        let chrs:Vec<char> = original_word.chars().collect();
-        let second_char = chrs[1];
-        if is_hyphen!(second_char) {
-            let word = original_word.to_lowercase();
-            if let Some(_entry) = dictionary.get(&word) {
-                return AnalysisResult::new(original_word, &word, true);
-            }
-            else {
-                return AnalysisResult::new(original_word, &word, false);
-            }
+}  // check_correlative
+
+
+/*
+ * check_apostrophe
+ *
+ * Handles apostrophes. In poetry and speech, the article 'la' can be elided to
+ * l', and the final -o of a substantive can be dropped: dom' = domo. An apostrophe
+ * at the start of a word, or after a word which is already valid, is treated as
+ * a quotation mark.
+ *
+ * Params:
+ *    original word
+ *    dictionary - a map of word data
+ *    options - settings for analysis
+ * Return:
+ *    optional analysis result (None if the word has no apostrophe)
+ */
+fn check_apostrophe(original_word: &str, dictionary: &Dictionary,
+                    options: &Options) -> Option<AnalysisResult> {
+
+    let chrs: Vec<char> = original_word.chars().collect();
+    let length = chrs.len();
+    if length < 2 { return None; }
+
+    let first = chrs[0];
+    let last = chrs[length - 1];
+
+    if is_apostrophe!(first) {
+        let rest: String = chrs[1..].iter().collect();
+        let mut result = check_word_with_options(&rest, dictionary, options);
+        result.word = format!("{}{}", first, result.word);
+        return Some(result);
+    }
+
+    // The elided article, l'. Sometimes it is joined to the next word: l'amiko.
+    if (first == 'l' || first == 'L') && is_apostrophe!(chrs[1]) {
+        if length == 2 {
+            let mut result = AnalysisResult::new(original_word, original_word, true);
+            result.part_of_speech = Some(POS::Article);
+            return Some(result);
         }
+        let rest: String = chrs[2..].iter().collect();
+        let mut result = check_word_with_options(&rest, dictionary, options);
+        result.word = format!("{}{}{}", first, chrs[1], result.word);
+        return Some(result);
     }
 
-    let original_word = remove_hyphens(&original_word);
-    let word = original_word.to_lowercase();
-    let length_of_word = word.chars().count();
+    if !is_apostrophe!(last) { return None; }
 
-    // Exceptions.
-    // A few words cause difficulties for the algorithm, especially accusative pronouns.
-    // For example, the pronoun 'vin' means 'you' (accusative), but it is also the root for 'wine' (vino).
-    // I want the pronoun to divided as 'vi.n' and the beverage to be 'vin.o' (not vi.n.o). The dictionary
-    // has 'vin' as a key, but the keys in a dictionary must be unique. To solve this problem, some
-    // pronouns (etc.) will be excluded from the dictionary, and handled as exceptions here.
+    // An elision follows a letter. ('', -')
+    if !chrs[length - 2].is_alphabetic() {
+        return Some(AnalysisResult::invalid(original_word, original_word, Invalidity::NoEnding));
+    }
 
-    if length_of_word < 5 {
-        let w;
-        match &word[..] {
-            "ĝin" => w = String::from("ĝi.n"),
-            "lin" => w = String::from("li.n"),
-            "min" => w = String::from("mi.n"),
-            "sin" => w = String::from("si.n"),
-            "vin" => w = String::from("vi.n"),
-            "lian" => w = String::from("li.an"),
-            "cian" => w = String::from("ci.an"),
-            _ => w = String::from(""),
-        }
-        if w.len() > 0 {
-            return AnalysisResult::new(&original_word, &w, true);
-        }
+    let without: String = chrs[..length - 1].iter().collect();
+    let mut result = check_word_with_options(&without, dictionary, options);
+    if result.valid {
+        result.word.push(last);
+        return Some(result);
     }
 
-    // First, check the dictionary for words which have no grammatical ending, eg. 'ne', 'dum', 'post'.
-    if let Some(entry) = dictionary.get(&word) {
-        if entry.without_ending == WithoutEnding::Yes {
-            return AnalysisResult::new(&original_word, &entry.word, true);
+    // Try a substantive with an elided -o.
+    let with_o = format!("{}o", without);
+    let mut result = check_word_with_options(&with_o, dictionary, options);
+    if result.valid && result.ending.as_deref() == Some("o") {
+        let mut w = result.word.clone();
+        w.pop();   // Remove the o.
+        if w.ends_with('.') { w.pop(); }
+        result.word = format!("{}{}", w, last);
+        return Some(result);
+    }
+
+    return Some(AnalysisResult::invalid(original_word, original_word, Invalidity::NoEnding));
+
+}  // check_apostrophe
+
+
+/*
+ * check_dotted_abbreviation
+ *
+ * Checks abbreviations which contain periods, such as 'k.t.p.' and 't.e.'.
+ * The dictionary is searched for the abbreviation with its periods ('t.e.'), and
+ * without them ('ktp'). The entry must be an abbreviation. Initials, such as 'L.L.',
+ * are also accepted.
+ *
+ * Params:
+ *    original word
+ *    dictionary - a map of word data
+ * Return:
+ *    optional analysis result (None if the word has no period)
+ */
+fn check_dotted_abbreviation(original_word: &str, dictionary: &Dictionary)
+                             -> Option<AnalysisResult> {
+
+    if !original_word.contains('.') { return None; }
+
+    // Every period must follow letters. '..', '.k' and 'k..t' are not abbreviations.
+    let mut parts: Vec<&str> = original_word.split('.').collect();
+    let last = parts.pop().unwrap_or("");
+    let letters = |part: &str| part.chars().all(|ch| is_word_char!(ch));
+    if !parts.iter().all(|part| !part.is_empty() && letters(part)) || !letters(last) {
+        return Some(AnalysisResult::invalid(original_word, original_word,
+                                            Invalidity::RootNotFound));
+    }
+
+    let dotted = original_word.to_lowercase();
+    let undotted = dotted.replace(".", "");
+
+    // The periods are not morpheme separators, so the word is kept unchanged.
+    let mut result = AnalysisResult::unchanged(original_word, false);
+
+    for key in [&dotted, &undotted].iter() {
+        if let Some(entry) = dictionary.get(*key) {
+            if entry.part_of_speech == POS::Abbreviation {
+                result.valid = true;
+                result.push_morpheme(entry);
+                result.part_of_speech = Some(POS::Abbreviation);
+                return Some(result);
+            }
         }
     }
 
-    let word_iter = word.chars();
+    // Initials, eg. 'L.L.'
+    let initials = original_word.split('.').filter(|s| !s.is_empty()).all(|s| {
+        let mut chrs = s.chars();
+        let first = chrs.next();
+        chrs.next().is_none() && first.is_some_and(|ch| ch.is_uppercase())
+    });
+    if initials {
+        result.valid = true;
+        return Some(result);
+    }
 
-    // Most words have a grammatical ending, eg. elefant-ojn, trov-is.
-    // Attempt to remove a grammatical ending, then search for the root, (elefant, trov).
-    if let Some(ending) = Ending::new(&word) {
-        let length = length_of_word - ending.length;
-        let word_without_ending: String = word_iter.take(length).collect();
+    result.reason = Some(Invalidity::RootNotFound);
+    return Some(result);
 
-        // Try to find the root in the dictionary.
-        if let Some(entry) = dictionary.get(&word_without_ending) {
-            if entry.with_ending == WithEnding::Yes {
-                let w = format!("{}.{}", &entry.word, ending.ending);
-                return AnalysisResult::new(&original_word, &w, true);
+}  // check_dotted_abbreviation
+
+
+/*
+ * check_number
+ *
+ * Numbers, such as '2020', are valid, as are ordinal numbers with a hyphen
+ * and an adjectival ending: '3-a', '21-aj', '5-an', '4-ajn'.
+ *
+ * Params:
+ *    original word
+ * Return:
+ *    optional analysis result (None if the word is not a number)
+ */
+fn check_number(original_word: &str) -> Option<AnalysisResult> {
+
+    let first_non_digit = original_word.find(|ch: char| !ch.is_ascii_digit());
+    let digits = match first_non_digit {
+        None => return Some(AnalysisResult::new(original_word, original_word, true)),
+        Some(0) => return None,
+        Some(index) => index,
+    };
+
+    let rest = &original_word[digits..];
+    let mut chrs = rest.chars();
+    if let Some(hyphen) = chrs.next() {
+        if is_hyphen!(hyphen) {
+            let ending = chrs.as_str();
+            match ending {
+                "a" | "aj" | "an" | "ajn" => {
+                    let mut result = AnalysisResult::new(original_word, original_word, true);
+                    result.part_of_speech = Some(POS::Adjective);
+                    result.ending = Some(String::from(ending));
+                    result.accusative = ending.ends_with('n');
+                    return Some(result);
+                },
+                _ => (),
             }
         }
+    }
+    return Some(AnalysisResult::invalid(original_word, original_word, Invalidity::NoEnding));
 
-        // The root was not found. Maybe it's a compound word.
-        // Analyze.
+}  // check_number
 
-        // The morpheme list needs the ending for later analysis.
-        let mut morpheme_list = Morphemes::new(ending);
 
-        let valid: bool = find_morpheme(&word_without_ending, dictionary, 0, &mut morpheme_list);
+// The digits, as number words. Compound numbers are made from these and 'dek', 'cent', 'mil'.
+const DIGITS: [&str; 9] = ["unu", "du", "tri", "kvar", "kvin", "ses", "sep", "ok", "naŭ"];
 
-        if valid {
-            return AnalysisResult::new(&original_word, &morpheme_list.display_form(), true);
+/*
+ * below_thousand
+ *
+ * Reads the part of a compound number which is below a thousand, eg. 'du', 'cent',
+ * 'dek', 'tri' of 'du.cent.dek.tri' (213). 'dek' and 'cent' can be multiplied by a
+ * digit other than 'unu' (du.dek, but not unu.dek).
+ *
+ * Params:
+ *    number words
+ *    index of the first word to read
+ * Return:
+ *    index after the last word read
+ */
+fn below_thousand(words: &[&str], index: usize) -> usize {
+    let mut index = index;
+    for multiple in ["cent", "dek"].iter() {
+        if words.get(index) == Some(multiple) {
+            index += 1;
         }
-        else {
-            return AnalysisResult::new(&original_word, &word, false);
+        else if words.get(index + 1) == Some(multiple) && words[index] != "unu" &&
+                DIGITS.contains(&words[index]) {
+            index += 2;
         }
+    }
+    if let Some(word) = words.get(index) {
+        if DIGITS.contains(word) { index += 1; }
+    }
+    return index;
+}  // below_thousand
+
 
+/*
+ * split_number_word
+ *
+ * Divides a compound number which has no ending, eg. 'dudekunu' (21), 'centmil'
+ * (100 000), into its number words. The number words must be in the usual order:
+ * thousands, hundreds, tens, units. Eg. 'dekdu' is valid, but not 'dudek' + 'dek'.
+ *
+ * Params:
+ *    word (lower case)
+ * Return:
+ *    number words, eg. ['du', 'dek', 'unu'], or None if the word is not a compound number
+ */
+fn split_number_word(word: &str) -> Option<Vec<&'static str>> {
+
+    let mut words: Vec<&'static str> = vec![];
+    let mut rest = word;
+    while !rest.is_empty() {
+        let number = DIGITS.iter().chain(["dek", "cent", "mil"].iter())
+                           .find(|number| rest.starts_with(*number))?;
+        words.push(number);
+        rest = &rest[number.len()..];
     }
-    else {
-        return AnalysisResult::new(&original_word, &word, false);
+    if words.len() < 2 { return None; }
+
+    // Thousands, eg. 'du.dek.mil'. ('unu.mil' is simply 'mil'.)
+    let mut index = below_thousand(&words, 0);
+    if words.get(index) == Some(&"mil") {
+        if index == 1 && words[0] == "unu" { return None; }
+        index = below_thousand(&words, index + 1);
+    }
+    if index < words.len() { return None; }
+    return Some(words);
+
+}  // split_number_word
+
+
+/*
+ * check_number_word
+ *
+ * Checks compound numbers which are written without an ending, eg. 'dudekunu'
+ * (du.dek.unu), 'centmil' (cent.mil). See split_number_word().
+ *
+ * Params:
+ *    original word
+ *    word (lower case, without hyphens)
+ *    dictionary - a map of word data
+ * Return:
+ *    analysis result, or None if the word is not a compound number
+ */
+fn check_number_word(original_word: &str, word: &str,
+                     dictionary: &Dictionary) -> Option<AnalysisResult> {
+    let words = split_number_word(word)?;
+    let mut result = AnalysisResult::new(original_word, &words.join("."), true);
+    for number in words {
+        if let Some(entry) = dictionary.get(number) {
+            result.push_morpheme(entry);
+        }
+    }
+    return Some(result);
+}  // check_number_word
+
+
+/*
+ * check_word
+ *
+ * This function tests whether a word is correctly spelled.
+ *
+ * Params:
+ *    word - the word to test
+ *    dictionary - a map of word data
+ * Return:
+ *     AnalysisResult
+ */
+pub fn check_word(original_word: &str, dictionary: &Dictionary) -> AnalysisResult {
+    return check_word_with_options(original_word, dictionary, &Options::default());
+}
+
+
+/*
+ * check_many
+ *
+ * Checks a list of words, as check_word() does.
+ *
+ * Params:
+ *    words - the words to test
+ *    dictionary - a map of word data
+ * Return:
+ *     list of AnalysisResults, in the order of the words
+ */
+pub fn check_many(words: &[&str], dictionary: &Dictionary) -> Vec<AnalysisResult> {
+    let options = Options::default();
+    return words.iter()
+                .map(|word| check_word_with_options(word, dictionary, &options))
+                .collect();
+}
+
+
+/*
+ * check_word_normalized
+ *
+ * Checks a word which the caller has already normalized with normalize(), that is:
+ * x-system letters have been converted to accented letters ('cx' -> 'ĉ'), hyphens
+ * and soft hyphens have been removed, and the word has been converted to lower case.
+ * This saves work when many words are checked, because check_word() removes hyphens
+ * and converts to lower case on every call. The result is the same as the result of
+ * check_word() for the normalized word. (Abbreviations with a hyphen, such as 'n-ro',
+ * lose their hyphen in normalize(), and are not recognized.)
+ *
+ * Params:
+ *    word - the normalized word, eg. 'ĉiutage'
+ *    dictionary - a map of word data
+ * Return:
+ *     AnalysisResult
+ */
+pub fn check_word_normalized(word: &str, dictionary: &Dictionary) -> AnalysisResult {
+    // Numbers, abbreviations (k.t.p.), elisions (dom') and single letters.
+    let mut length_of_word = 0;
+    for ch in word.chars() {
+        if !ch.is_alphabetic() { return check_word(word, dictionary); }
+        length_of_word += 1;
+    }
+    if length_of_word < 2 { return check_word(word, dictionary); }
+    return check_lower_case(word, word, dictionary, &Options::default());
+}
+
+
+/*
+ * is_valid
+ *
+ * This function tests whether a word is correctly spelled, as check_word() does,
+ * but only returns true or false. For ordinary words (letters only), it does not
+ * build an analysis result, restore capitals or join the morphemes for display.
+ * Other words, such as numbers, abbreviations and elisions, are passed to
+ * check_word().
+ *
+ * Params:
+ *    word - the word to test
+ *    dictionary - a map of word data
+ * Return:
+ *    true if the word is correctly spelled
+ */
+pub fn is_valid(original_word: &str, dictionary: &Dictionary) -> bool {
+
+    let options = Options::default();
+
+    let mut length_of_word = 0;
+    for ch in original_word.chars() {
+        if !ch.is_alphabetic() {
+            return check_word_with_options(original_word, dictionary, &options).valid;
+        }
+        length_of_word += 1;
+    }
+    if length_of_word < 2 {
+        return check_word_with_options(original_word, dictionary, &options).valid;
+    }
+
+    let word = original_word.to_lowercase();
+
+    // Exceptions. See check_word_with_options().
+    if exceptions(&options).contains_key(&word) { return true; }
+    if split_pronoun_adjective(&word).is_some() { return true; }
+
+    if let Some(result) = check_correlative(&word) { return result.valid; }
+
+    if let Some(entry) = dictionary.get(&word) {
+        if entry.without_ending == WithoutEnding::Yes { return true; }
+    }
+    if split_number_word(&word).is_some() { return true; }
+
+    if let Some(ending) = Ending::new(&word) {
+        let word_without_ending = &word[..word.len() - ending.ending.len()];
+        if let Some(entry) = dictionary.get(word_without_ending) {
+            if entry.with_ending == WithEnding::Yes { return true; }
+        }
+        let mut morpheme_list = Morphemes::new(ending, options.max_morphemes);
+        return find_morpheme(word_without_ending, dictionary, 0, &mut morpheme_list, &options);
+    }
+    return false;
+
+}  // is_valid
+
+
+/*
+ * analyze_all
+ *
+ * This function finds every valid division of a word into morphemes, whereas
+ * check_word() stops at the first. The division given by check_word() comes first.
+ * For example, a word might be divided as 'X.Y.o' and as 'XY.o'.
+ * The search is exhaustive, so this is slower than check_word().
+ *
+ * Params:
+ *    word - the word to analyze
+ *    dictionary - a map of word data
+ * Return:
+ *    list of divisions, without duplicates (empty if the word is invalid)
+ */
+pub fn analyze_all(original_word: &str, dictionary: &Dictionary) -> Vec<String> {
+
+    let mut divisions: Vec<String> = vec![];
+
+    let result = check_word(original_word, dictionary);
+    if !result.valid { return divisions; }
+    divisions.push(result.word);
+
+    let original_word = remove_hyphens(original_word);
+    let word = original_word.to_lowercase();
+    if let Some(ending) = Ending::new(&word) {
+        let word_without_ending = &word[..word.len() - ending.ending.len()];
+        let options = Options::default();
+        let mut morpheme_list = Morphemes::new(ending, options.max_morphemes);
+        morpheme_list.collect_divisions();
+        find_morpheme(word_without_ending, dictionary, 0, &mut morpheme_list, &options);
+        for division in morpheme_list.division_forms() {
+            let division = restore_capitals(&original_word, &division);
+            if !divisions.contains(&division) { divisions.push(division); }
+        }
+    }
+    return divisions;
+
+}  // analyze_all
+
+
+/*
+ * missing_root
+ *
+ * For a word which cannot be divided into known morphemes, finds the longest
+ * beginning of the word which can be, to show which root is missing from the
+ * dictionary. Eg. for 'mislernfrobo', the known part is 'mis.lern', and the
+ * unknown rest is 'frobo'. The rest includes the grammatical ending.
+ *
+ * Params:
+ *    word - the word to analyze
+ *    dictionary - a map of word data
+ * Return:
+ *    (known morphemes, unknown rest), or None if the word is valid, or if all of
+ *    its morphemes are known, but they do not combine validly
+ */
+pub fn missing_root(original_word: &str, dictionary: &Dictionary) -> Option<(String, String)> {
+
+    if is_valid(original_word, dictionary) { return None; }
+
+    let original_word = remove_hyphens(original_word);
+    let word = original_word.to_lowercase();
+    let mut covered = 0;
+    let mut known = String::new();
+    if let Some(ending) = Ending::new(&word) {
+        let word_without_ending = &word[..word.len() - ending.ending.len()];
+        let options = Options::default();
+        let mut morpheme_list = Morphemes::new(ending, options.max_morphemes);
+        morpheme_list.track_progress();
+        find_morpheme(word_without_ending, dictionary, 0, &mut morpheme_list, &options);
+        let (progress, division) = morpheme_list.progress();
+        if progress == word_without_ending.len() { return None; }
+        covered = word[..progress].chars().count();
+        known = division;
+    }
+    let rest: String = original_word.chars().skip(covered).collect();
+    return Some((known, rest));
+
+}  // missing_root
+
+
+/*
+ * combines_with
+ *
+ * Determines whether a word made from morphemes is valid, and divided as expected.
+ * Each ending (o, a, i, e) is tried in turn, eg. 'sen.frat.a' is valid, but not
+ * 'sen.frat.o'. (See explain_synthesis().)
+ *
+ * Params:
+ *    morphemes, eg. ['bo', 'frat']
+ *    dictionary - a map of word data
+ * Return:
+ *    true if some ending makes a valid word with this division
+ */
+fn combines_with(morphemes: &[&str], dictionary: &Dictionary) -> bool {
+    let joined = morphemes.join(".");
+    for ending in ["o", "a", "i", "e"].iter() {
+        let expected = format!("{}.{}", joined, ending);
+        let word = expected.replace(".", "");
+        let result = check_word(&word, dictionary);
+        if result.valid && result.word == expected { return true; }
+    }
+    return false;
+}  // combines_with
+
+
+/*
+ * explain_synthesis
+ *
+ * Explains how a root of the dictionary combines with prefixes and suffixes,
+ * eg. for choosing the synthesis code (NLM, LM, P, S) of a new root. Each prefix
+ * and suffix of the dictionary is tried with the root, and those which make a valid
+ * word are listed. Technical prefixes, such as 'hiper', are not tried.
+ * Eg. for 'frat':
+ *
+ *    frat: Substantive, Parenco, synthesis UnLimited
+ *    prefixes: al, anstataŭ, antaŭ, apud, bis, bo, ...
+ *    suffixes: aĉ, ad, aĵ, ar, ec, ...
+ *
+ * Params:
+ *    root, eg. 'frat' (accents may be represented by x)
+ *    dictionary - a map of word data
+ * Return:
+ *    explanation, in three lines
+ */
+pub fn explain_synthesis(root: &str, dictionary: &Dictionary) -> String {
+
+    let key = x_to_accent(root).to_lowercase();
+    let entry = match dictionary.get(&key) {
+        Some(entry) => entry,
+        None => return format!("{}: not in the dictionary", root),
+    };
+
+    let mut prefixes: Vec<&str> = vec![];
+    let mut suffixes: Vec<&str> = vec![];
+    for affix in dictionary.values() {
+        if affix.synthesis == Synthesis::Prefix && affix.part_of_speech != POS::TechPrefix {
+            if combines_with(&[&affix.word, &entry.word], dictionary) {
+                prefixes.push(&affix.word);
+            }
+        }
+        else if affix.synthesis == Synthesis::Suffix &&
+                combines_with(&[&entry.word, &affix.word], dictionary) {
+            suffixes.push(&affix.word);
+        }
+    }
+
+    // Sorted by the x-system, so that 'ĉe' follows 'cis', as in the alphabet.
+    let list = |mut affixes: Vec<&str>| {
+        if affixes.is_empty() { return String::from("(none)"); }
+        affixes.sort_by_key(|affix| accent_to_x(affix));
+        return affixes.join(", ");
+    };
+    return format!("{}: {:?}, {:?}, synthesis {:?}\nprefixes: {}\nsuffixes: {}",
+                   entry.word, entry.part_of_speech, entry.meaning, entry.synthesis,
+                   list(prefixes), list(suffixes));
+
+}  // explain_synthesis
+
+
+// The personal pronouns. Their accusatives are formed by adding -n, eg. 'vi.n'.
+const PRONOUNS: [&str; 10] = ["mi", "vi", "li", "ŝi", "ĝi", "ni", "ili", "oni", "si", "ci"];
+
+// The default exceptions are made on first use.
+static EXCEPTIONS: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/*
+ * default_exceptions
+ *
+ * Makes the default map of exceptions. Exceptions are words which are divided
+ * from a table, before the dictionary is searched. (See check_lower_case().)
+ * The keys are words in lower case, and the values are their divisions. By
+ * default, these are the accusatives of the personal pronouns, eg. 'vin' -> 'vi.n'.
+ *
+ * Return:
+ *    map of exceptions
+ */
+pub fn default_exceptions() -> HashMap<String, String> {
+    let mut exceptions = HashMap::new();
+    for pronoun in PRONOUNS.iter() {
+        exceptions.insert(format!("{}n", pronoun), format!("{}.n", pronoun));
+    }
+    return exceptions;
+}
+
+/*
+ * exceptions
+ *
+ * Params:
+ *    options
+ * Return:
+ *    the map of exceptions of the options, or the default map
+ */
+fn exceptions(options: &Options) -> &HashMap<String, String> {
+    if let Some(exceptions) = &options.exceptions { return exceptions; }
+    return EXCEPTIONS.get_or_init(default_exceptions);
+}
+
+/*
+ * check_exception
+ *
+ * Divides a word which is in the map of exceptions, eg. 'vin' -> 'vi.n'.
+ * The last part of the division is the ending, if there is more than one part.
+ * The other parts are looked up in the dictionary. An ending in -n is accusative.
+ *
+ * Params:
+ *    original word
+ *    word (lower case)
+ *    dictionary - a map of word data
+ *    options
+ * Return:
+ *    analysis result, or None if the word is not an exception
+ */
+fn check_exception(original_word: &str, word: &str, dictionary: &Dictionary,
+                   options: &Options) -> Option<AnalysisResult> {
+    let division = exceptions(options).get(word)?;
+    let mut result = AnalysisResult::new(original_word, division, true);
+    let mut parts: Vec<&str> = division.split('.').collect();
+    if parts.len() > 1 {
+        if let Some(ending) = parts.pop() {
+            result.accusative = ending.ends_with('n');
+        }
+    }
+    for part in parts {
+        if let Some(entry) = dictionary.get(part) {
+            result.push_morpheme(entry);
+        }
+    }
+    return Some(result);
+}  // check_exception
+
+
+/*
+ * split_pronoun_adjective
+ *
+ * Divides a possessive adjective into a personal pronoun and an adjective ending,
+ * eg. 'niajn' -> ('ni', 'ajn').
+ *
+ * Params:
+ *    word (lower case)
+ * Return:
+ *    pronoun and ending, or None if the word is not a possessive adjective
+ */
+fn split_pronoun_adjective(word: &str) -> Option<(&str, &str)> {
+    for ending in ["ajn", "aj", "an", "a"].iter() {
+        if let Some(pronoun) = word.strip_suffix(ending) {
+            if PRONOUNS.contains(&pronoun) { return Some((pronoun, ending)); }
+        }
+    }
+    return None;
+}
+
+
+/*
+ * check_pronoun_adjective
+ *
+ * Recognizes possessive adjectives, which are a personal pronoun followed by an
+ * adjective ending: -a, -aj, -an, -ajn. Eg. 'mi.a', 'ili.ajn'. These do not depend
+ * on dictionary entries for each form. Some of them, such as 'lian', would otherwise
+ * be taken for noun roots.
+ *
+ * Params:
+ *    original word
+ *    word (lower case, without hyphens)
+ *    dictionary - a map of word data
+ * Return:
+ *    analysis result, or None if the word is not a possessive adjective
+ */
+fn check_pronoun_adjective(original_word: &str, word: &str,
+                           dictionary: &Dictionary) -> Option<AnalysisResult> {
+    let (pronoun, ending) = split_pronoun_adjective(word)?;
+    let w = format!("{}.{}", pronoun, ending);
+    let mut result = match dictionary.get(pronoun) {
+        Some(entry) => AnalysisResult::with_entry(original_word, &w, entry),
+        None => AnalysisResult::new(original_word, &w, true),
+    };
+    result.part_of_speech = Some(POS::PronounAdjective);
+    result.ending = Some(String::from(ending));
+    result.accusative = ending.ends_with('n');
+    return Some(result);
+}  // check_pronoun_adjective
+
+
+/*
+ * split_hyphenated
+ *
+ * Divides a word at its hyphens (but not at soft hyphens, which are removed).
+ *
+ * Params:
+ *    original word
+ * Return:
+ *    parts of the word, and the hyphens between them
+ */
+fn split_hyphenated(original_word: &str) -> (Vec<String>, Vec<char>) {
+    let mut parts = vec![String::new()];
+    let mut hyphens = vec![];
+    for ch in original_word.chars() {
+        if ch == '\u{AD}' { continue; }
+        if is_hyphen!(ch) {
+            hyphens.push(ch);
+            parts.push(String::new());
+        }
+        else if let Some(part) = parts.last_mut() {
+            part.push(ch);
+        }
+    }
+    return (parts, hyphens);
+}  // split_hyphenated
+
+
+/*
+ * restore_hyphens
+ *
+ * Puts the hyphens of the original word back into an analysis of the word
+ * without hyphens. A hyphen takes the place of a period between morphemes,
+ * eg. 'NovJork.o' becomes 'Nov-Jork-o'.
+ *
+ * Params:
+ *    original word
+ *    analyzed word, without hyphens
+ * Return:
+ *    analyzed word, with hyphens
+ */
+fn restore_hyphens(original_word: &str, analyzed: &str) -> String {
+    let mut original = original_word.chars().filter(|&ch| ch != '\u{AD}').peekable();
+    let mut restored = String::new();
+    for ch in analyzed.chars() {
+        let mut hyphen = false;
+        while let Some(&next) = original.peek() {
+            if !is_hyphen!(next) { break; }
+            restored.push(next);
+            original.next();
+            hyphen = true;
+        }
+        if ch == '.' {
+            if !hyphen { restored.push(ch); }
+            continue;
+        }
+        original.next();
+        restored.push(ch);
+    }
+    restored.extend(original);
+    return restored;
+}  // restore_hyphens
+
+
+/*
+ * check_hyphenated
+ *
+ * Checks words whose roots are joined by hyphens, such as 'ruĝ-verd-a',
+ * 'sud-afrika' or 'Nov-Jork-o'. The last part has the grammatical ending,
+ * or is the ending. Each root is checked with that ending, eg. 'ruĝa' and
+ * 'verda', and the hyphens are kept: 'ruĝ-verd-a', 'sud-afrik.a'. A part before
+ * the last may also end with a separator vowel, eg. 'hund.o-kurs.o'.
+ * If a root is not valid by itself, eg. 'Jork', the word is analyzed without
+ * its hyphens, and the hyphens are put back: 'Nov-Jork-o'.
+ *
+ * Params:
+ *    original word
+ *    dictionary - a map of word data
+ *    options
+ * Return:
+ *    analysis result, or None if the word is not joined by hyphens
+ */
+fn check_hyphenated(original_word: &str, dictionary: &Dictionary,
+                    options: &Options) -> Option<AnalysisResult> {
+
+    let (parts, hyphens) = split_hyphenated(original_word);
+    if hyphens.is_empty() || parts.iter().any(|part| part.is_empty()) { return None; }
+
+    if let Some(result) = check_hyphenated_roots(&parts, &hyphens, dictionary, options) {
+        return Some(result);
+    }
+
+    let mut result = check_word_with_options(&parts.concat(), dictionary, options);
+    result.word = restore_hyphens(original_word, &result.word);
+    return Some(result);
+
+}  // check_hyphenated
+
+
+/*
+ * check_hyphenated_roots
+ *
+ * Checks each root of a hyphenated word with the word's ending. See check_hyphenated().
+ *
+ * Params:
+ *    parts of the word
+ *    hyphens between the parts
+ *    dictionary - a map of word data
+ *    options
+ * Return:
+ *    analysis result, or None if a root is not valid with the ending
+ */
+fn check_hyphenated_roots(parts: &[String], hyphens: &[char], dictionary: &Dictionary,
+                          options: &Options) -> Option<AnalysisResult> {
+
+    let word = parts.concat().to_lowercase();
+    let ending = Ending::new(&word)?;
+    let last = &parts[parts.len() - 1];
+    let last_lower = last.to_lowercase();
+    if !last_lower.ends_with(ending.ending) { return None; }
+
+    // The last part is either the ending itself (ruĝ-verd-a), or a root with the ending (sud-afrika).
+    let mut roots: Vec<&str> = parts.iter().map(|part| part.as_str()).collect();
+    let ending_is_part = last_lower == ending.ending;
+    if ending_is_part { roots.pop(); }
+    else { roots[parts.len() - 1] = &last[..last.len() - ending.ending.len()]; }
+
+    let mut result = AnalysisResult::new(&word, &word, true);
+    let mut display = String::new();
+    let suffix = format!(".{}", ending.ending);
+    for (index, root) in roots.iter().enumerate() {
+        if index > 0 { display.push(hyphens[index - 1]); }
+        let mut root_result = check_word_with_options(&format!("{}{}", root, ending.ending),
+                                                      dictionary, options);
+        if root_result.valid {
+            display.push_str(root_result.word.strip_suffix(&suffix)?);
+        }
+        else {
+            // A part before the last one may end with a separator vowel, as in
+            // 'hund.o-kurs.o'. Otherwise, 'hundokurso' would be divided 'hund.ok.urs.o'.
+            if index + 1 == roots.len() { return None; }
+            root_result = check_word_with_options(root, dictionary, options);
+            if !root_result.valid || root_result.morphemes.is_empty() { return None; }
+            let separator = Entry::new_separator(root_result.ending.as_deref()?)?;
+            root_result.push_morpheme(&separator);
+            display.push_str(&root_result.word);
+        }
+        result.morphemes.extend(root_result.morphemes);
+        result.rarity = match (result.rarity, root_result.rarity) {
+            (Some(r1), Some(r2)) => Some(r1.max(r2)),
+            (r1, r2) => r1.or(r2),
+        };
+    }
+    if ending_is_part {
+        display.push(hyphens[hyphens.len() - 1]);
+        display.push_str(last);
+    }
+    else {
+        display.push('.');
+        display.push_str(&last[last.len() - ending.ending.len()..]);
+    }
+    result.word = display;
+    result.set_ending(&ending);
+    return Some(result);
+
+}  // check_hyphenated_roots
+
+
+/*
+ * check_capitals
+ *
+ * Names are capitalized in the dictionary, eg. 'Uson', 'Kanad'. When a name is
+ * written in lower case ('usono', 'kanadon'), the word is invalid. Words derived
+ * from names, such as 'usona' and 'kanadano', are usually written in lower case,
+ * so only nouns which have a single morpheme are checked.
+ *
+ * Params:
+ *    original word
+ *    analysis result
+ * Return:
+ *    analysis result, invalid if a name is not capitalized
+ */
+fn check_capitals(original_word: &str, result: AnalysisResult) -> AnalysisResult {
+    if !result.valid || result.morphemes.len() != 1 ||
+       result.part_of_speech != Some(POS::Substantive) { return result; }
+    let is_name = result.morphemes[0].word.chars().next().is_some_and(|ch| ch.is_uppercase());
+    let is_lower = original_word.chars().next().is_some_and(|ch| ch.is_lowercase());
+    if is_name && is_lower {
+        return AnalysisResult::invalid(original_word, &result.word, Invalidity::Uncapitalized);
+    }
+    return result;
+}  // check_capitals
+
+
+/*
+ * check_abbreviation_caps
+ *
+ * Some abbreviations are written in capitals in the dictionary, eg. 'UEA', 'TEJO'.
+ * They are only valid in capitals, because in lower case, 'uea' is not a word, and
+ * 'un' is a misspelling of 'unu'. Unlike check_capitals(), this check is always made.
+ *
+ * Params:
+ *    original word
+ *    analysis result
+ * Return:
+ *    analysis result, invalid if the abbreviation is not in capitals
+ */
+fn check_abbreviation_caps(original_word: &str, result: AnalysisResult) -> AnalysisResult {
+    if !result.valid || result.morphemes.len() != 1 ||
+       result.morphemes[0].part_of_speech != POS::Abbreviation { return result; }
+    let in_capitals = result.morphemes[0].word.chars().any(|ch| ch.is_uppercase());
+    if in_capitals && original_word.chars().any(|ch| ch.is_lowercase()) {
+        return AnalysisResult::invalid(original_word, &result.word, Invalidity::Uncapitalized);
+    }
+    return result;
+}  // check_abbreviation_caps
+
+
+/*
+ * check_word_with_options
+ *
+ * This function tests whether a word is correctly spelled, according to the given options.
+ *
+ * Params:
+ *    word - the word to test
+ *    dictionary - a map of word data
+ *    options - settings for analysis, eg. the maximum number of morphemes
+ * Return:
+ *     AnalysisResult
+ */
+pub fn check_word_with_options(original_word: &str, dictionary: &Dictionary,
+                               options: &Options) -> AnalysisResult {
+    let result = check_spelling(original_word, dictionary, options);
+    let result = check_abbreviation_caps(original_word, result);
+    if options.strict_caps { return check_capitals(original_word, result); }
+    return result;
+}
+
+
+/*
+ * check_spelling
+ *
+ * Tests whether a word is correctly spelled, without regard to capitalization.
+ * See check_word_with_options().
+ *
+ * Params:
+ *    word - the word to test
+ *    dictionary - a map of word data
+ *    options - settings for analysis
+ * Return:
+ *     AnalysisResult
+ */
+fn check_spelling(original_word: &str, dictionary: &Dictionary,
+                  options: &Options) -> AnalysisResult {
+
+    // Letters with combining accents, eg. 'c' + U+0302, are made precomposed ('ĉ'),
+    // as in the dictionary. (See compose_accents().)
+    if original_word.contains(['\u{302}', '\u{306}']) {
+        return check_spelling(&compose_accents(original_word), dictionary, options);
+    }
+
+    // Soft hyphens are invisible. They mark where a word may be broken at the end
+    // of a line, eg. 'kon\u{AD}strui', so they are removed before the checks below,
+    // which take a visible hyphen as part of an abbreviation ('n-ro') or a compound.
+    if original_word.contains('\u{AD}') {
+        let without: String = original_word.chars().filter(|&ch| ch != '\u{AD}').collect();
+        return check_spelling(&without, dictionary, options);
+    }
+
+    // Numbers, such as '2020' and '3-a'.
+    if let Some(result) = check_number(original_word) { return result; }
+
+    let length_of_word = original_word.chars().count();
+
+    if length_of_word == 1 {   // Single letters are OK.
+        let chrs:Vec<char> = original_word.chars().collect();
+        if is_word_char!(chrs[0]) && !is_apostrophe!(chrs[0]) {
+            return AnalysisResult::new(original_word, original_word, true);
+        }
+        else {
+            return AnalysisResult::invalid(original_word, original_word, Invalidity::RootNotFound);
+        }
+    }
+
+    // Check for abbreviations with periods, such as k.t.p. and t.e.
+    if let Some(result) = check_dotted_abbreviation(original_word, dictionary) {
+        return result;
+    }
+
+    // Check for elisions, such as dom' and l'.
+    if let Some(result) = check_apostrophe(original_word, dictionary, options) {
+        return result;
+    }
+
+    // Check for abbreviations, such as n-r.oj, s-in.oj
+    // The second character must be a hyphen.
+    if length_of_word > 2 {
+        let chrs:Vec<char> = original_word.chars().collect();
+        let second_char = chrs[1];
+        if is_hyphen!(second_char) {
+            // The dictionary has hyphen-minus, eg. 'n-ro', not 'n‑ro'.
+            let word: String = original_word.to_lowercase().chars()
+                .map(|ch| if ch == '\u{2010}' || ch == '\u{2011}' { '-' } else { ch })
+                .collect();
+            if let Some(entry) = dictionary.get(&word) {
+                return AnalysisResult::with_entry(original_word, &word, entry);
+            }
+            else {
+                return AnalysisResult::invalid(original_word, &word, Invalidity::RootNotFound);
+            }
+        }
+    }
+
+    // Roots joined by hyphens, such as ruĝ-verd-a.
+    if let Some(result) = check_hyphenated(original_word, dictionary, options) {
+        return result;
+    }
+
+    let original_word = remove_hyphens(original_word);
+    let word = original_word.to_lowercase();
+    return check_lower_case(&original_word, &word, dictionary, options);
+
+}  // check_spelling
+
+
+/*
+ * check_lower_case
+ *
+ * Checks a word which has no hyphens. The word has been converted to lower case,
+ * but the original is kept, to restore capitals for display. (See check_spelling().)
+ *
+ * Params:
+ *    original word (without hyphens)
+ *    word - the word in lower case
+ *    dictionary - a map of word data
+ *    options - settings for analysis
+ * Return:
+ *     AnalysisResult
+ */
+fn check_lower_case(original_word: &str, word: &str, dictionary: &Dictionary,
+                    options: &Options) -> AnalysisResult {
+
+    let length_of_word = word.chars().count();
+
+    // Exceptions.
+    // A few words cause difficulties for the algorithm, especially accusative pronouns.
+    // For example, the pronoun 'vin' means 'you' (accusative), but it is also the root for 'wine' (vino).
+    // I want the pronoun to divided as 'vi.n' and the beverage to be 'vin.o' (not vi.n.o). The dictionary
+    // has 'vin' as a key, but the keys in a dictionary must be unique. To solve this problem, the
+    // accusative pronouns are recognized from a table (see default_exceptions()), before the
+    // dictionary is searched.
+
+    if let Some(result) = check_exception(original_word, word, dictionary, options) {
+        return result;
+    }
+
+    // Possessive adjectives, such as 'mia', 'viajn', 'lian' (li.an, not lian.o).
+    if let Some(result) = check_pronoun_adjective(original_word, word, dictionary) {
+        return result;
+    }
+
+    // Correlatives (tabelvortoj), such as 'kiu', 'tiam', 'ĉiujn'.
+    if let Some(result) = check_correlative(original_word) { return result; }
+
+    // First, check the dictionary for words which have no grammatical ending, eg. 'ne', 'dum', 'post'.
+    if let Some(entry) = dictionary.get(word) {
+        if entry.without_ending == WithoutEnding::Yes {
+            let mut result = AnalysisResult::with_entry(original_word, &entry.word, entry);
+            // Adverbs of direction, such as 'supr.en', are in the dictionary with
+            // their accusative ending.
+            if entry.word.ends_with(".en") {
+                if let Some(ending) = Ending::new(word) { result.set_ending(&ending); }
+            }
+            return result;
+        }
+    }
+
+    // Compound numbers, which need no ending, eg. 'dudekunu', 'centmil'.
+    if let Some(result) = check_number_word(original_word, word, dictionary) {
+        return result;
+    }
+
+    let word_iter = word.chars();
+
+    // Most words have a grammatical ending, eg. elefant-ojn, trov-is.
+    // Attempt to remove a grammatical ending, then search for the root, (elefant, trov).
+    if let Some(ending) = Ending::new(word) {
+        let length = length_of_word - ending.length;
+        let word_without_ending: String = word_iter.take(length).collect();
+
+        // Try to find the root in the dictionary.
+        // A prefix, such as mis, re or dis, cannot take an ending alone, as in 'misa'.
+        // find_morpheme() rejects it. Prepositions and interjections which are used
+        // as prefixes can, eg. 'kun.e', 'bis.i'.
+        if let Some(entry) = dictionary.get(&word_without_ending) {
+            let prefix_only = entry.synthesis == Synthesis::Prefix &&
+                              matches!(entry.part_of_speech, POS::Adverb | POS::Prefix);
+            if entry.with_ending == WithEnding::Yes && !prefix_only {
+                let w = format!("{}.{}", &entry.word, ending.ending);
+                let mut result = AnalysisResult::with_entry(original_word, &w, entry);
+                result.set_ending(&ending);
+                return result;
+            }
+        }
+
+        // The root was not found. Maybe it's a compound word.
+        // Analyze.
+
+        // The morpheme list needs the ending for later analysis.
+        // A word may be divided in more than one way. The first division found has
+        // the longest first morphemes. It is replaced only by a division with fewer
+        // morphemes, eg. 'sen.depend.a' is preferred to 'send.e.pend.a'.
+        let mut morpheme_list = Morphemes::new(ending, options.max_morphemes);
+        morpheme_list.seek_fewer();
+
+        find_morpheme(&word_without_ending, dictionary, 0, &mut morpheme_list, options);
+        let valid = morpheme_list.use_fewest();
+
+        if valid {
+            let mut result = AnalysisResult::new(original_word, &morpheme_list.display_form(), true);
+            for entry in morpheme_list.iter() {
+                result.push_morpheme(entry);
+            }
+            result.set_ending(morpheme_list.ending());
+            return result;
+        }
+        else {
+            return AnalysisResult::invalid(original_word, word, morpheme_list.failure());
+        }
+
+    }
+    else {
+        return AnalysisResult::invalid(original_word, word, Invalidity::NoEnding);
     }  // No ending.
 
-}  // check_word
+}  // check_lower_case
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use super::super::vortaro::load_precompiled;
+
+    // Checks a word with the built-in dictionary, and returns the analysis, or
+    // 'invalid' for a misspelled word.
+    fn analyze(word: &str) -> String {
+        let result = check_word(word, &load_precompiled().unwrap());
+        if result.valid { return result.word; }
+        return String::from("invalid");
+    }
+
+    // A prefix cannot be the last root.
+    #[test]
+    fn prefix_as_last_root() {
+        let dictionary = load_precompiled().unwrap();
+        for (word, index) in [("misa", 0), ("rea", 0), ("disa", 0), ("malrea", 1)].iter() {
+            let result = check_word(word, &dictionary);
+            assert!(!result.valid, "{}", word);
+            assert_eq!(result.reason, Some(Invalidity::BadSynthesis { index: *index }), "{}", word);
+        }
+        assert_eq!(analyze("misdiris"), "mis.dir.is");
+        assert_eq!(analyze("realigis"), "real.ig.is");
+    }
+
+    // The first division, with the longest first morphemes, is kept unless another
+    // has fewer morphemes.
+    #[test]
+    fn longest_first_division() {
+        assert_eq!(analyze("mildeco"), "mild.ec.o");
+        assert_eq!(analyze("venkanta"), "venk.ant.a");
+        assert_eq!(analyze("provizita"), "proviz.it.a");
+        assert_eq!(analyze("inspirita"), "inspir.it.a");
+        assert_eq!(analyze("Marokulo"), "Marok.ul.o");
+        assert_eq!(analyze("supligi"), "supl.ig.i");
+        assert_eq!(analyze("farmita"), "farm.it.a");
+        assert_eq!(analyze("sendependa"), "sen.depend.a");
+    }
+
+    // Number roots combine, and take endings for ordinals and fractions.
+    #[test]
+    fn ordinals_and_fractions() {
+        assert_eq!(analyze("kvara"), "kvar.a");
+        assert_eq!(analyze("triono"), "tri.on.o");
+        assert_eq!(analyze("dudeka"), "du.dek.a");
+        assert_eq!(analyze("dekdua"), "dek.du.a");
+        assert_eq!(analyze("dekduono"), "dek.du.on.o");
+        let result = check_word("dekdua", &load_precompiled().unwrap());
+        assert_eq!(result.part_of_speech, Some(POS::Adjective));
+    }
+
+    // Compound numbers need no ending. (See split_number_word().)
+    #[test]
+    fn number_words() {
+        assert_eq!(split_number_word("dudekunu"), Some(vec!["du", "dek", "unu"]));
+        assert_eq!(split_number_word("centmil"), Some(vec!["cent", "mil"]));
+        assert_eq!(split_number_word("unumil"), None);
+        assert_eq!(split_number_word("dekdek"), None);
+        assert_eq!(split_number_word("du"), None);
+        let dictionary = load_precompiled().unwrap();
+        let result = check_number_word("Dudekunu", "dudekunu", &dictionary).unwrap();
+        assert!(result.valid);
+        assert_eq!(result.word, "Du.dek.unu");
+        assert_eq!(result.morphemes.len(), 3);
+        assert!(check_number_word("dudeka", "dudeka", &dictionary).is_none());
+    }
+
+    #[test]
+    fn dotted_abbreviations() {
+        assert_eq!(analyze("k.t.p."), "k.t.p.");
+        assert_eq!(analyze("T.e."), "T.e.");
+        assert_eq!(analyze("L.L."), "L.L.");
+        for word in ["..", ".", "k..t.", ".k", "k.t.p.."].iter() {
+            assert_eq!(analyze(word), "invalid", "{}", word);
+        }
+    }
+
+    // Correlatives (tabelvortoj) are divided before their plural and accusative endings.
+    #[test]
+    fn correlatives() {
+        assert_eq!(analyze("kiu"), "kiu");
+        assert_eq!(analyze("Nenial"), "Nenial");
+        assert_eq!(analyze("tiujn"), "tiu.jn");
+        assert_eq!(analyze("kion"), "kio.n");
+        assert_eq!(analyze("ĉiuj"), "ĉiu.j");
+        for word in ["kiuo", "tiamn", "nenieo", "kioj"].iter() {
+            assert_eq!(analyze(word), "invalid", "{}", word);
+        }
+    }
+
+    // The suffix -io makes a country from an ethnic root. Most countries are
+    // in the dictionary, eg. 'Svedi'.
+    #[test]
+    fn country_suffix_io() {
+        assert_eq!(analyze("Finnio"), "Finn.i.o");
+        assert_eq!(analyze("Finnion"), "Finn.i.on");
+        assert_eq!(analyze("Svedio"), "Svedi.o");
+        assert_eq!(analyze("hundio"), "invalid");
+        assert_eq!(analyze("Kanadio"), "invalid");
+    }
+
+    // Each root of a hyphenated compound is checked with the ending, and the
+    // hyphens are kept between the morphemes.
+    #[test]
+    fn hyphenated_compounds() {
+        assert_eq!(analyze("ruĝ-verda"), "ruĝ-verd.a");
+        assert_eq!(analyze("ruĝ-verd-a"), "ruĝ-verd-a");
+        assert_eq!(analyze("sud-afrika"), "sud-afrik.a");
+        assert_eq!(analyze("Nov-Jorko"), "Nov-Jork.o");
+        assert_eq!(analyze("hundo-kurso"), "hund.o-kurs.o");
+        assert_eq!(analyze("ruĝa-verda"), "ruĝ.a-verd.a");
+        assert_eq!(analyze("ruĝ\u{2011}verda"), "ruĝ\u{2011}verd.a");
+        for word in ["la-hundo", "ruĝ-xyz-a"].iter() {
+            assert_eq!(analyze(word), "invalid", "{}", word);
+        }
+    }
+
+    // The article and the -o of a substantive can be elided. (See check_apostrophe().)
+    #[test]
+    fn elision() {
+        assert_eq!(analyze("l'"), "l'");
+        assert_eq!(analyze("dom'"), "dom'");
+        assert_eq!(analyze("mond’"), "mond’");
+        assert_eq!(analyze("l'amiko"), "l'amik.o");
+        for word in ["'", "''", "-'", "hundoi'"].iter() {
+            assert_eq!(analyze(word), "invalid", "{}", word);
+        }
+    }
+
+    // Soft hyphens, which mark where a word may be broken, are ignored.
+    #[test]
+    fn soft_hyphen() {
+        assert_eq!(analyze("kon\u{AD}strui"), "konstru.i");
+    }
+
+    // A long string of short morphemes, which cannot be divided because of the
+    // last letter, must be rejected soon after pruning begins. (See min_morphemes().)
+    #[test]
+    fn long_invalid_word_is_pruned() {
+        let dictionary = load_precompiled().unwrap();
+        let word = "ararararararararararararararararararararararararq";
+        let options = Options { max_morphemes: 30, ..Options::default() };
+        let mut morpheme_list = Morphemes::new(Ending::new("aro").unwrap(), options.max_morphemes);
+        morpheme_list.seek_fewer();
+        assert!(!find_morpheme(word, &dictionary, 0, &mut morpheme_list, &options));
+        assert!(morpheme_list.steps() < 2 * PRUNE_AFTER_STEPS);
+        let result = check_word_with_options(&format!("{}o", word), &dictionary, &options);
+        assert!(!result.valid);
+    }
+
+}  // tests
@@ -0,0 +1,59 @@
+// embedded - This module has functions which use the dictionary embedded in the
+// library. They return strings, and never read files or print, so they can be
+// used where there is no file system or standard output, eg. in WebAssembly.
+// Klivo 2020-05-28
+
+use std::sync::OnceLock;
+
+use super::vortaro::*;
+use super::check_word::*;
+use super::file_report::*;
+
+// The embedded dictionary is loaded on first use. These functions return strings
+// only, so a bad dictionary cannot be reported. It is written by build.rs, and
+// can only be invalid if the build itself is broken.
+static DICTIONARY: OnceLock<Dictionary> = OnceLock::new();
+
+fn dictionary() -> &'static Dictionary {
+    return DICTIONARY.get_or_init(|| load_precompiled().expect("Invalid embedded dictionary."));
+}
+
+/*
+ * analyze_string()
+ *
+ * Analyzes a text with the embedded dictionary. In morpheme mode, the text is
+ * returned with each known word divided into morphemes, eg. 'La elefant.oj.'
+ * Otherwise, the misspelled words are returned, one per line, in the order
+ * in which they first appear.
+ *
+ * Params:
+ *    text
+ *    morpheme mode
+ * Return:
+ *    analyzed text, or list of misspelled words
+ */
+pub fn analyze_string(text: &str, morpheme_mode: bool) -> String {
+    let report = analyze_text(text, dictionary(), morpheme_mode);
+    if let Some(analyzed_text) = report.analyzed_text { return analyzed_text; }
+    let mut output = String::new();
+    for word in report.misspelled {
+        output.push_str(&word);
+        output.push('\n');
+    }
+    return output;
+}  // analyze_string()
+
+/*
+ * check_one()
+ *
+ * Checks one word with the embedded dictionary.
+ *
+ * Params:
+ *    word
+ * Return:
+ *    word divided into morphemes (if valid), and validity
+ */
+pub fn check_one(word: &str) -> (String, bool) {
+    let result = check_word(word, dictionary());
+    return (result.word, result.valid);
+}  // check_one()
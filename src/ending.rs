@@ -4,6 +4,7 @@
 #![allow(dead_code)]
 
 use super::entry::*;
+use super::x_to_accent;
 
 #[derive(Debug)]
 pub struct Ending<'a> {
@@ -32,16 +33,24 @@ const ADV_EN: Ending = Ending { ending: "en", length: 2, pos: POS::Adverb, };
 
 impl Ending<'_> {
 
+    // is_accusative - The accusative endings are -n, -jn and the directional -en.
+    // For adverbs, the accusative indicates direction. Eg. hejm.en (homeward).
+    pub fn is_accusative(&self) -> bool { self.ending.ends_with('n') }
+
     // new - Checks to see if the original word has a valid grammatical ending.
     // Returns and Option containing an Ending struct, or None.
+    // Words which end in -aŭ or -eŭ, such as 'hodiaŭ', 'ankaŭ' and 'antaŭ', are
+    // invariable. They have no grammatical ending, and must be found in the
+    // dictionary as words without an ending. ('ŭ' is not the verb ending 'u'.)
 
-    pub fn new(original_word: &str) -> Option<Ending> {
+    pub fn new(original_word: &str) -> Option<Ending<'_>> {
 
         let length = original_word.chars().count();
         let mut characters = original_word.chars().rev();
 
         if let Some(last) = characters.next() {
             if length < 3 { return None; }
+            if last == 'ŭ' || last == 'Ŭ' { return None; }   // hodiaŭ, ankaŭ
             if last == 'o' {
                 return Some(SUB_O);
             }
@@ -93,3 +102,92 @@ impl Ending<'_> {
         else { None }
     }
 }
+
+
+/*
+ * Features
+ * Grammatical features indicated by the ending of a word.
+ * Fields:
+ *     'case' - nominative or accusative, for substantives, adjectives and
+ *              adverbs (hejm.en). None for verbs.
+ *     'number' - singular or plural, for substantives and adjectives. None otherwise.
+ *     'pos_ending' - the part of speech indicated by the ending.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Features {
+    pub case: Option<Case>,
+    pub number: Option<Plurality>,
+    pub pos_ending: POS,
+}
+
+/*
+ * grammatical_features()
+ *
+ * Gets the case, number and part of speech of a word from its grammatical ending.
+ * The dictionary is not consulted, so the word is not validated. Eg. for
+ * 'elefantojn' the features are accusative, plural, substantive, and for 'kuris',
+ * verb. Accents can be represented by 'x'.
+ *
+ * Params:
+ *    word
+ * Return:
+ *    features, or None if the word has no grammatical ending (eg. 'dum')
+ */
+pub fn grammatical_features(word: &str) -> Option<Features> {
+    let word = x_to_accent(&word.to_lowercase());
+    let ending = Ending::new(&word)?;
+    let features = match ending.pos {
+        POS::Verb => Features { case: None, number: None, pos_ending: POS::Verb },
+        POS::Adverb => Features {
+            case: Some(Case::new(ending.ending)),
+            number: None,
+            pos_ending: POS::Adverb,
+        },
+        pos => Features {
+            case: Some(Case::new(ending.ending)),
+            number: Some(Plurality::new(ending.ending)),
+            pos_ending: pos,
+        },
+    };
+    return Some(features);
+}  // grammatical_features
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use super::super::check_word::check_word;
+    use super::super::vortaro::load_precompiled;
+
+    #[test]
+    fn words_with_u_breve() {
+        assert_eq!(Ending::new("ĉirkaŭe").map(|e| e.ending), Some("e"));
+        assert!(Ending::new("antaŭ").is_none());
+        assert_eq!(Ending::new("eŭropo").map(|e| e.ending), Some("o"));
+
+        let dictionary = load_precompiled().unwrap();
+        assert_eq!(check_word("ĉirkaŭe", &dictionary).word, "ĉirkaŭ.e");
+        let result = check_word("antaŭ", &dictionary);
+        assert!(result.valid);
+        assert_eq!(result.word, "antaŭ");
+        assert_eq!(check_word("Eŭropo", &dictionary).word, "Eŭrop.o");
+    }
+
+    // Adverbs of direction take the accusative, whether the dictionary has the
+    // root (hejm), the adverb (tie) or the whole word (supr.en).
+    #[test]
+    fn adverbs_of_direction() {
+        let dictionary = load_precompiled().unwrap();
+        for (word, analyzed) in [("hejmen", "hejm.en"), ("tien", "tie.n"),
+                                 ("supren", "supr.en")].iter() {
+            let result = check_word(word, &dictionary);
+            assert_eq!(result.word, *analyzed);
+            assert!(result.accusative, "{}", word);
+            assert_eq!(result.part_of_speech, Some(POS::Adverb), "{}", word);
+        }
+        let result = check_word("hejme", &dictionary);
+        assert!(!result.accusative);
+    }
+
+}  // tests
@@ -1,55 +1,81 @@
 // Make an entry for the Esperanto spell-checking dictionary.
 // Klivo 2020-05-08
 
-use literumilo::*;
+use super::*;
 
 // Part of Speech - also defines role in morphology.
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum POS {
+    #[cfg_attr(feature = "serde", serde(rename = "SUBST"))]
     Substantive,	// = noun
+    #[cfg_attr(feature = "serde", serde(rename = "SUBSTVERBO"))]
     SubstantiveVerb,
+    #[cfg_attr(feature = "serde", serde(rename = "VERBO"))]
     Verb,
+    #[cfg_attr(feature = "serde", serde(rename = "ADJ"))]
     Adjective,
+    #[cfg_attr(feature = "serde", serde(rename = "NUMERO"))]
     Number,
+    #[cfg_attr(feature = "serde", serde(rename = "ADVERBO"))]
     Adverb,
+    #[cfg_attr(feature = "serde", serde(rename = "PRONOMO"))]
     Pronoun,
+    #[cfg_attr(feature = "serde", serde(rename = "PRONOMADJ"))]
     PronounAdjective,
+    #[cfg_attr(feature = "serde", serde(rename = "PREPOZICIO"))]
     Preposition,
+    #[cfg_attr(feature = "serde", serde(rename = "KONJUNKCIO"))]
     Conjunction,
+    #[cfg_attr(feature = "serde", serde(rename = "SUBJUNKCIO"))]
     Subjunction,
+    #[cfg_attr(feature = "serde", serde(rename = "INTERJEKCIO"))]
     Interjection,
+    #[cfg_attr(feature = "serde", serde(rename = "PREFIKSO"))]
     Prefix,
+    #[cfg_attr(feature = "serde", serde(rename = "TEHXPREFIKSO"))]
     TechPrefix,	// technical prefix,(hiper-, mega-) - not used independently
+    #[cfg_attr(feature = "serde", serde(rename = "SUFIKSO"))]
     Suffix,
+    #[cfg_attr(feature = "serde", serde(rename = "ARTIKOLO"))]
     Article,
+    #[cfg_attr(feature = "serde", serde(rename = "PARTICIPO"))]
     Participle,
+    #[cfg_attr(feature = "serde", serde(rename = "MALLONGIGO"))]
     Abbreviation,	// UEA, UNESKO
+    #[cfg_attr(feature = "serde", serde(rename = "LITERO"))]
     Letter,
 }
 
 impl POS {
+    // Unknown codes default to POS::Substantive. See parse().
     pub fn new(s: &str) -> POS {
+        return POS::parse(s).unwrap_or(POS::Substantive);
+    }
+
+    // Returns None if the code is unknown.
+    pub fn parse(s: &str) -> Option<POS> {
         match s {
-            "SUBST" => POS::Substantive,
-            "ADVERBO" => POS::Adverb,
-            "INTERJEKCIO" => POS::Interjection,
-            "MALLONGIGO" => POS::Abbreviation,
-            "VERBO" => POS::Verb,
-            "ADJ" => POS::Adjective,
-            "PRONOMADJ" => POS::PronounAdjective,
-            "SUFIKSO" => POS::Suffix,
-            "PREPOZICIO" => POS::Preposition,
-            "LITERO" => POS::Letter,
-            "TEHXPREFIKSO" => POS::TechPrefix,
-            "PRONOMO" => POS::Pronoun,
-            "PARTICIPO" => POS::Participle,
-            "KONJUNKCIO" => POS::Conjunction,
-            "NUMERO" => POS::Number,
-            "SUBJUNKCIO" => POS::Subjunction,
-            "ARTIKOLO" => POS::Article,
-            "PREFIKSO" => POS::Prefix,
-            "SUBSTVERBO" => POS::SubstantiveVerb,
-            _ => POS::Substantive,
+            "SUBST" => Some(POS::Substantive),
+            "ADVERBO" => Some(POS::Adverb),
+            "INTERJEKCIO" => Some(POS::Interjection),
+            "MALLONGIGO" => Some(POS::Abbreviation),
+            "VERBO" => Some(POS::Verb),
+            "ADJ" => Some(POS::Adjective),
+            "PRONOMADJ" => Some(POS::PronounAdjective),
+            "SUFIKSO" => Some(POS::Suffix),
+            "PREPOZICIO" => Some(POS::Preposition),
+            "LITERO" => Some(POS::Letter),
+            "TEHXPREFIKSO" => Some(POS::TechPrefix),
+            "PRONOMO" => Some(POS::Pronoun),
+            "PARTICIPO" => Some(POS::Participle),
+            "KONJUNKCIO" => Some(POS::Conjunction),
+            "NUMERO" => Some(POS::Number),
+            "SUBJUNKCIO" => Some(POS::Subjunction),
+            "ARTIKOLO" => Some(POS::Article),
+            "PREFIKSO" => Some(POS::Prefix),
+            "SUBSTVERBO" => Some(POS::SubstantiveVerb),
+            _ => None,
         }
     }
 }  // POS
@@ -57,6 +83,7 @@ impl POS {
 
  // Capitalization: miniscule, majuscule, entirely majuscule.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Capitalization {
     Miniscule,		// butero
     Majuscule,		// Kanado
@@ -68,16 +95,14 @@ pub enum Capitalization {
 impl Capitalization {
     pub fn new(s: &str) -> Capitalization {
         let mut iter = s.chars();
-        let unua;
-        let dua;
-        match iter.next() {
+        let unua = match iter.next() {
             None => panic!("Capitalization::new : La literĉeno estas malplena."),
-            Some(c) => { unua = c.is_uppercase(); }
-        }
-        match iter.next() {
-            None => { dua = false; },
-            Some(c) => { dua = c.is_uppercase(); }
-        }
+            Some(c) => c.is_uppercase(),
+        };
+        let dua = match iter.next() {
+            None => false,
+            Some(c) => c.is_uppercase(),
+        };
         if unua && dua { return Capitalization::AllCaps; }
         if unua { return Capitalization::Majuscule; }
         Capitalization::Miniscule
@@ -88,7 +113,10 @@ impl Capitalization {
 // 'Meaning' defines the domain of a word. For example, 'bizon', 'cerv' and
 // 'hipopotam' have a meaning 'Mamulo' (Mammal).
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "UPPERCASE"))]
 pub enum Meaning {
+    #[cfg_attr(feature = "serde", serde(rename = "N"))]
     NeKonata,	// unknown - most common domain
     Legomo,
     Boato,
@@ -103,7 +131,9 @@ pub enum Meaning {
     Loko,
     Drogo,
     Lago,
+    #[cfg_attr(feature = "serde", serde(rename = "PSEUXDOSCI"))]
     Pseuxdoscienco,
+    #[cfg_attr(feature = "serde", serde(rename = "RELPOSTENO"))]
     ReligiaPosteno,
     Profesio,
     Gramatiko,
@@ -157,7 +187,9 @@ pub enum Meaning {
     Sporto,
     Elemento,
     Alojo,
+    #[cfg_attr(feature = "serde", serde(rename = "RELPERSONO"))]
     ReligiaPersono,
+    #[cfg_attr(feature = "serde", serde(rename = "RELPROFESIO"))]
     ReligiaProfesio,
     Kemiajxo,
     Filozofio,
@@ -179,6 +211,7 @@ pub enum Meaning {
     Mikrobo,
     Ludo,
     Dezerto,
+    #[cfg_attr(feature = "serde", serde(rename = "MITBESTO"))]
     MitaBesto,
     Dramo,
     Vetero,
@@ -195,6 +228,7 @@ pub enum Meaning {
     Mezurilo,
     Maro,
     Montaro,
+    #[cfg_attr(feature = "serde", serde(rename = "MITPERSONO"))]
     MitaPersono,
     Fonetiko,
     Monero,
@@ -210,127 +244,133 @@ pub enum Meaning {
 }
 
 impl Meaning {
+    // Unknown codes default to Meaning::NeKonata. See parse().
     pub fn new(s: &str) -> Meaning {
+        return Meaning::parse(s).unwrap_or(Meaning::NeKonata);
+    }
+
+    // Returns None if the code is unknown.
+    pub fn parse(s: &str) -> Option<Meaning> {
         match s {
-            "N" => Meaning::NeKonata,
-            "LEGOMO" => Meaning::Legomo,
-            "BOATO" => Meaning::Boato,
-            "KRUSTULO" => Meaning::Krustulo,
-            "INSULO" => Meaning::Insulo,
-            "RELIGIO" => Meaning::Religio,
-            "HERBO" => Meaning::Herbo,
-            "KOLORO" => Meaning::Koloro,
-            "PLANTO" => Meaning::Planto,
-            "FESTO" => Meaning::Festo,
-            "LIBRO" => Meaning::Libro,
-            "LOKO" => Meaning::Loko,
-            "DROGO" => Meaning::Drogo,
-            "LAGO" => Meaning::Lago,
-            "PSEUXDOSCI" => Meaning::Pseuxdoscienco,
-            "RELPOSTENO" => Meaning::ReligiaPosteno,
-            "PROFESIO" => Meaning::Profesio,
-            "GRAMATIKO" => Meaning::Gramatiko,
-            "EHXINODERMO" => Meaning::Ehxinodermo,
-            "MEDIKAMENTO" => Meaning::Medikamento,
-            "REGIONO" => Meaning::Regiono,
-            "BIOLOGIO" => Meaning::Biologio,
-            "BIRDO" => Meaning::Birdo,
-            "URBO" => Meaning::Urbo,
-            "VETURILO" => Meaning::Veturilo,
-            "LANDO" => Meaning::Lando,
-            "ETNO" => Meaning::Etno,
-            "KANTO" => Meaning::Kanto,
-            "VESTAJXO" => Meaning::Vestajxo,
-            "TITOLO" => Meaning::Titolo,
-            "REGANTO" => Meaning::Reganto,
-            "RIVERO" => Meaning::Rivero,
-            "ARTO" => Meaning::Arto,
-            "ERAO" => Meaning::Erao,
-            "PROVINCO" => Meaning::Provinco,
-            "MUZIKO" => Meaning::Muziko,
-            "PERSONO" => Meaning::Persono,
-            "SXTATO" => Meaning::Sxtato,
-            "MAMULO" => Meaning::Mamulo,
-            "FISXO" => Meaning::Fisxo,
-            "MEZURUNUO" => Meaning::Mezurunuo,
-            "FUNGO" => Meaning::Fungo,
-            "KURACARTO" => Meaning::Kuracarto,
-            "ARMILO" => Meaning::Armilo,
-            "ALGO" => Meaning::Algo,
-            "KOELENTERO" => Meaning::Koelentero,
-            "NUKSO" => Meaning::Nukso,
-            "MONTO" => Meaning::Monto,
-            "GEOGRAFIO" => Meaning::Geografio,
-            "TEHXNOLOGIO" => Meaning::Tehxnologio,
-            "MONATO" => Meaning::Monato,
-            "ARKITEKTURO" => Meaning::Arkitekturo,
-            "INSULARO" => Meaning::Insularo,
-            "METIO" => Meaning::Metio,
-            "ASTRONOMIO" => Meaning::Astronomio,
-            "KREDO" => Meaning::Kredo,
-            "MOLUSKO" => Meaning::Molusko,
-            "REPTILIO" => Meaning::Reptilio,
-            "TRINKAJXO" => Meaning::Trinkajxo,
-            "ANIMALO" => Meaning::Animalo,
-            "INSEKTO" => Meaning::Insekto,
-            "FRUKTO" => Meaning::Frukto,
-            "ARBUSTO" => Meaning::Arbusto,
-            "ARAKNIDO" => Meaning::Araknido,
-            "AVIADILO" => Meaning::Aviadilo,
-            "SPORTO" => Meaning::Sporto,
-            "ELEMENTO" => Meaning::Elemento,
-            "ALOJO" => Meaning::Alojo,
-            "RELPERSONO" => Meaning::ReligiaPersono,
-            "RELPROFESIO" => Meaning::ReligiaProfesio,
-            "KEMIAJXO" => Meaning::Kemiajxo,
-            "FILOZOFIO" => Meaning::Filozofio,
-            "SXTOFO" => Meaning::Sxtofo,
-            "POSTENO" => Meaning::Posteno,
-            "PARENCO" => Meaning::Parenco,
-            "KONSTRUAJXO" => Meaning::Konstruajxo,
-            "CEREALO" => Meaning::Cerealo,
-            "DANCO" => Meaning::Danco,
-            "TAGO" => Meaning::Tago,
-            "POEMO" => Meaning::Poemo,
-            "SXIPO" => Meaning::Sxipo,
-            "LUDILO" => Meaning::Ludilo,
-            "POEZIO" => Meaning::Poezio,
-            "CXAMBRO" => Meaning::Cxambro,
-            "MANGXAJXO" => Meaning::Mangxajxo,
-            "ASTRO" => Meaning::Astro,
-            "ILO" => Meaning::Ilo,
-            "MIKROBO" => Meaning::Mikrobo,
-            "LUDO" => Meaning::Ludo,
-            "DEZERTO" => Meaning::Dezerto,
-            "MITBESTO" => Meaning::MitaBesto,
-            "DRAMO" => Meaning::Dramo,
-            "VETERO" => Meaning::Vetero,
-            "ARBO" => Meaning::Arbo,
-            "SCIENCO" => Meaning::Scienco,
-            "ORNAMAJXO" => Meaning::Ornamajxo,
-            "VERMO" => Meaning::Vermo,
-            "MINERALO" => Meaning::Mineralo,
-            "SPICO" => Meaning::Spico,
-            "MASXINO" => Meaning::Masxino,
-            "KONTINENTO" => Meaning::Kontinento,
-            "PERIODO" => Meaning::Periodo,
-            "LINGVO" => Meaning::Lingvo,
-            "MEZURILO" => Meaning::Mezurilo,
-            "MARO" => Meaning::Maro,
-            "MONTARO" => Meaning::Montaro,
-            "MITPERSONO" => Meaning::MitaPersono,
-            "FONETIKO" => Meaning::Fonetiko,
-            "MONERO" => Meaning::Monero,
-            "MATEMATIKO" => Meaning::Matematiko,
-            "RANGO" => Meaning::Rango,
-            "ANATOMIO" => Meaning::Anatomio,
-            "STUDO" => Meaning::Studo,
-            "OPTIKO" => Meaning::Optiko,
-            "AMFIBIO" => Meaning::Amfibio,
-            "MALSANO" => Meaning::Malsano,
-            "MUZIKILO" => Meaning::Muzikilo,
-            "GEOMETRIO" => Meaning::Geometrio,
-            _ => Meaning::NeKonata,
+            "N" => Some(Meaning::NeKonata),
+            "LEGOMO" => Some(Meaning::Legomo),
+            "BOATO" => Some(Meaning::Boato),
+            "KRUSTULO" => Some(Meaning::Krustulo),
+            "INSULO" => Some(Meaning::Insulo),
+            "RELIGIO" => Some(Meaning::Religio),
+            "HERBO" => Some(Meaning::Herbo),
+            "KOLORO" => Some(Meaning::Koloro),
+            "PLANTO" => Some(Meaning::Planto),
+            "FESTO" => Some(Meaning::Festo),
+            "LIBRO" => Some(Meaning::Libro),
+            "LOKO" => Some(Meaning::Loko),
+            "DROGO" => Some(Meaning::Drogo),
+            "LAGO" => Some(Meaning::Lago),
+            "PSEUXDOSCI" => Some(Meaning::Pseuxdoscienco),
+            "RELPOSTENO" => Some(Meaning::ReligiaPosteno),
+            "PROFESIO" => Some(Meaning::Profesio),
+            "GRAMATIKO" => Some(Meaning::Gramatiko),
+            "EHXINODERMO" => Some(Meaning::Ehxinodermo),
+            "MEDIKAMENTO" => Some(Meaning::Medikamento),
+            "REGIONO" => Some(Meaning::Regiono),
+            "BIOLOGIO" => Some(Meaning::Biologio),
+            "BIRDO" => Some(Meaning::Birdo),
+            "URBO" => Some(Meaning::Urbo),
+            "VETURILO" => Some(Meaning::Veturilo),
+            "LANDO" => Some(Meaning::Lando),
+            "ETNO" => Some(Meaning::Etno),
+            "KANTO" => Some(Meaning::Kanto),
+            "VESTAJXO" => Some(Meaning::Vestajxo),
+            "TITOLO" => Some(Meaning::Titolo),
+            "REGANTO" => Some(Meaning::Reganto),
+            "RIVERO" => Some(Meaning::Rivero),
+            "ARTO" => Some(Meaning::Arto),
+            "ERAO" => Some(Meaning::Erao),
+            "PROVINCO" => Some(Meaning::Provinco),
+            "MUZIKO" => Some(Meaning::Muziko),
+            "PERSONO" => Some(Meaning::Persono),
+            "SXTATO" => Some(Meaning::Sxtato),
+            "MAMULO" => Some(Meaning::Mamulo),
+            "FISXO" => Some(Meaning::Fisxo),
+            "MEZURUNUO" => Some(Meaning::Mezurunuo),
+            "FUNGO" => Some(Meaning::Fungo),
+            "KURACARTO" => Some(Meaning::Kuracarto),
+            "ARMILO" => Some(Meaning::Armilo),
+            "ALGO" => Some(Meaning::Algo),
+            "KOELENTERO" => Some(Meaning::Koelentero),
+            "NUKSO" => Some(Meaning::Nukso),
+            "MONTO" => Some(Meaning::Monto),
+            "GEOGRAFIO" => Some(Meaning::Geografio),
+            "TEHXNOLOGIO" => Some(Meaning::Tehxnologio),
+            "MONATO" => Some(Meaning::Monato),
+            "ARKITEKTURO" => Some(Meaning::Arkitekturo),
+            "INSULARO" => Some(Meaning::Insularo),
+            "METIO" => Some(Meaning::Metio),
+            "ASTRONOMIO" => Some(Meaning::Astronomio),
+            "KREDO" => Some(Meaning::Kredo),
+            "MOLUSKO" => Some(Meaning::Molusko),
+            "REPTILIO" => Some(Meaning::Reptilio),
+            "TRINKAJXO" => Some(Meaning::Trinkajxo),
+            "ANIMALO" => Some(Meaning::Animalo),
+            "INSEKTO" => Some(Meaning::Insekto),
+            "FRUKTO" => Some(Meaning::Frukto),
+            "ARBUSTO" => Some(Meaning::Arbusto),
+            "ARAKNIDO" => Some(Meaning::Araknido),
+            "AVIADILO" => Some(Meaning::Aviadilo),
+            "SPORTO" => Some(Meaning::Sporto),
+            "ELEMENTO" => Some(Meaning::Elemento),
+            "ALOJO" => Some(Meaning::Alojo),
+            "RELPERSONO" => Some(Meaning::ReligiaPersono),
+            "RELPROFESIO" => Some(Meaning::ReligiaProfesio),
+            "KEMIAJXO" => Some(Meaning::Kemiajxo),
+            "FILOZOFIO" => Some(Meaning::Filozofio),
+            "SXTOFO" => Some(Meaning::Sxtofo),
+            "POSTENO" => Some(Meaning::Posteno),
+            "PARENCO" => Some(Meaning::Parenco),
+            "KONSTRUAJXO" => Some(Meaning::Konstruajxo),
+            "CEREALO" => Some(Meaning::Cerealo),
+            "DANCO" => Some(Meaning::Danco),
+            "TAGO" => Some(Meaning::Tago),
+            "POEMO" => Some(Meaning::Poemo),
+            "SXIPO" => Some(Meaning::Sxipo),
+            "LUDILO" => Some(Meaning::Ludilo),
+            "POEZIO" => Some(Meaning::Poezio),
+            "CXAMBRO" => Some(Meaning::Cxambro),
+            "MANGXAJXO" => Some(Meaning::Mangxajxo),
+            "ASTRO" => Some(Meaning::Astro),
+            "ILO" => Some(Meaning::Ilo),
+            "MIKROBO" => Some(Meaning::Mikrobo),
+            "LUDO" => Some(Meaning::Ludo),
+            "DEZERTO" => Some(Meaning::Dezerto),
+            "MITBESTO" => Some(Meaning::MitaBesto),
+            "DRAMO" => Some(Meaning::Dramo),
+            "VETERO" => Some(Meaning::Vetero),
+            "ARBO" => Some(Meaning::Arbo),
+            "SCIENCO" => Some(Meaning::Scienco),
+            "ORNAMAJXO" => Some(Meaning::Ornamajxo),
+            "VERMO" => Some(Meaning::Vermo),
+            "MINERALO" => Some(Meaning::Mineralo),
+            "SPICO" => Some(Meaning::Spico),
+            "MASXINO" => Some(Meaning::Masxino),
+            "KONTINENTO" => Some(Meaning::Kontinento),
+            "PERIODO" => Some(Meaning::Periodo),
+            "LINGVO" => Some(Meaning::Lingvo),
+            "MEZURILO" => Some(Meaning::Mezurilo),
+            "MARO" => Some(Meaning::Maro),
+            "MONTARO" => Some(Meaning::Montaro),
+            "MITPERSONO" => Some(Meaning::MitaPersono),
+            "FONETIKO" => Some(Meaning::Fonetiko),
+            "MONERO" => Some(Meaning::Monero),
+            "MATEMATIKO" => Some(Meaning::Matematiko),
+            "RANGO" => Some(Meaning::Rango),
+            "ANATOMIO" => Some(Meaning::Anatomio),
+            "STUDO" => Some(Meaning::Studo),
+            "OPTIKO" => Some(Meaning::Optiko),
+            "AMFIBIO" => Some(Meaning::Amfibio),
+            "MALSANO" => Some(Meaning::Malsano),
+            "MUZIKILO" => Some(Meaning::Muzikilo),
+            "GEOMETRIO" => Some(Meaning::Geometrio),
+            _ => None,
         }
     }
 }  // end of impl Meaning
@@ -346,22 +386,20 @@ impl Meaning {
  *     true if person, false otherwise
  */
 pub fn is_person(meaning: Meaning) -> bool {
-    let person = match meaning {
-        Meaning::Persono => true,
-        Meaning::Parenco => true,
-        Meaning::Etno => true,
-        Meaning::Profesio => true,
-        Meaning::Rango => true,
-        Meaning::Reganto => true,
-        Meaning::Titolo => true,
-        Meaning::Posteno => true,
-        Meaning::ReligiaPosteno => true,
-        Meaning::ReligiaPersono => true,
-        Meaning::ReligiaProfesio => true,
-        Meaning::MitaPersono => true,
-        _ => false,
-    };
-    return person;
+    return matches!(meaning,
+        Meaning::Persono |
+        Meaning::Parenco |
+        Meaning::Etno |
+        Meaning::Profesio |
+        Meaning::Rango |
+        Meaning::Reganto |
+        Meaning::Titolo |
+        Meaning::Posteno |
+        Meaning::ReligiaPosteno |
+        Meaning::ReligiaPersono |
+        Meaning::ReligiaProfesio |
+        Meaning::MitaPersono
+    );
 }
 
 /*
@@ -374,37 +412,44 @@ pub fn is_person(meaning: Meaning) -> bool {
  *     true if person, false otherwise
  */
 pub fn is_animal(meaning: Meaning) -> bool {
-    let animal = match meaning {
-        Meaning::Animalo => true,
-        Meaning::Mamulo => true,
-        Meaning::Birdo => true,
-        Meaning::Fisxo => true,
-        Meaning::Reptilio => true,
-        Meaning::MitaBesto => true,
-        Meaning::Insekto => true,
-        Meaning::Araknido => true,
-        Meaning::Molusko => true,
-        Meaning::Amfibio => true,
-        _ => false,
-    };
-    return animal;
+    return matches!(meaning,
+        Meaning::Animalo |
+        Meaning::Mamulo |
+        Meaning::Birdo |
+        Meaning::Fisxo |
+        Meaning::Reptilio |
+        Meaning::MitaBesto |
+        Meaning::Insekto |
+        Meaning::Araknido |
+        Meaning::Molusko |
+        Meaning::Amfibio
+    );
 }
 
 
 // Transitivity - property of verbs
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Transitivity {
+    #[cfg_attr(feature = "serde", serde(rename = "T"))]
     Transitive,	// vidis
+    #[cfg_attr(feature = "serde", serde(rename = "N"))]
     Intransitive,	// dormas
     Both,		// ludas
 }
 
 impl Transitivity {
+    // Unknown codes default to Transitivity::Both. See parse().
     pub fn new(s: &str) -> Transitivity {
+        return Transitivity::parse(s).unwrap_or(Transitivity::Both);
+    }
+
+    // Returns None if the code is unknown.
+    pub fn parse(s: &str) -> Option<Transitivity> {
         match s {
-            "T" => Transitivity::Transitive,
-            "N" => Transitivity::Intransitive,
-            _ => Transitivity::Both,
+            "T" => Some(Transitivity::Transitive),
+            "N" => Some(Transitivity::Intransitive),
+            _ => None,
         }
     }
 }
@@ -413,16 +458,26 @@ impl Transitivity {
 // This property indicates whether a morpheme can stand without an
 // ending. For example, 'dum' doesn't require an ending, but 'vintr' does (vintro).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum WithoutEnding {
+    #[cfg_attr(feature = "serde", serde(rename = "SF"))]
     Yes,
+    #[cfg_attr(feature = "serde", serde(rename = "N"))]
     No,
 }
 
 impl WithoutEnding {
+    // Unknown codes default to WithoutEnding::No. See parse().
     pub fn new(s: &str) -> WithoutEnding {
+        return WithoutEnding::parse(s).unwrap_or(WithoutEnding::No);
+    }
+
+    // Returns None if the code is unknown.
+    pub fn parse(s: &str) -> Option<WithoutEnding> {
         match s {
-            "SF" => WithoutEnding::Yes,
-            _ => WithoutEnding::No,
+            "SF" => Some(WithoutEnding::Yes),
+            "N" => Some(WithoutEnding::No),
+            _ => None,
         }
     }
 }
@@ -433,16 +488,26 @@ impl WithoutEnding {
 // For example, 'arb' takes an ending (generally 'o'), but 'bo-' generally does not.
 // It must act as a prefix ('bopatro').
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum WithEnding {
+    #[cfg_attr(feature = "serde", serde(rename = "KF"))]
     Yes,
+    #[cfg_attr(feature = "serde", serde(rename = "N"))]
     No,
 }
 
 impl WithEnding {
+    // Unknown codes default to WithEnding::No. See parse().
     pub fn new(s: &str) -> WithEnding {
+        return WithEnding::parse(s).unwrap_or(WithEnding::No);
+    }
+
+    // Returns None if the code is unknown.
+    pub fn parse(s: &str) -> Option<WithEnding> {
         match s {
-            "KF" => WithEnding::Yes,
-            _ => WithEnding::No,
+            "KF" => Some(WithEnding::Yes),
+            "N" => Some(WithEnding::No),
+            _ => None,
         }
     }
 }
@@ -452,24 +517,38 @@ impl WithEnding {
 // Synthesis - Defines limits on morphology.
 // Difinas kiel radikoj kuniĝas en morfologio.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Synthesis {
+    #[cfg_attr(feature = "serde", serde(rename = "S"))]
     Suffix,	// The morpheme acts like a suffix.
+    #[cfg_attr(feature = "serde", serde(rename = "P"))]
     Prefix,	// The morpheme acts like a prefix.
+    #[cfg_attr(feature = "serde", serde(rename = "PRT"))]
     Participle,	// The morpheme acts like a participle ending (-int, -it, etc.)
+    #[cfg_attr(feature = "serde", serde(rename = "LM"))]
     Limited,	// Limited combinability.
+    #[cfg_attr(feature = "serde", serde(rename = "NLM"))]
     UnLimited,
+    #[cfg_attr(feature = "serde", serde(rename = "N"))]
     No,   // Does not combine.
 }
 
 impl Synthesis {
+    // Unknown codes default to Synthesis::No. See parse().
     pub fn new(s: &str) -> Synthesis {
+        return Synthesis::parse(s).unwrap_or(Synthesis::No);
+    }
+
+    // Returns None if the code is unknown.
+    pub fn parse(s: &str) -> Option<Synthesis> {
         match s {
-            "S" => Synthesis::Suffix,
-            "P" => Synthesis::Prefix,
-            "PRT" => Synthesis::Participle,
-            "LM" => Synthesis::Limited,
-            "NLM" => Synthesis::UnLimited,
-            _ => Synthesis::No,
+            "S" => Some(Synthesis::Suffix),
+            "P" => Some(Synthesis::Prefix),
+            "PRT" => Some(Synthesis::Participle),
+            "LM" => Some(Synthesis::Limited),
+            "NLM" => Some(Synthesis::UnLimited),
+            "N" => Some(Synthesis::No),
+            _ => None,
         }
     }
 }
@@ -478,19 +557,30 @@ impl Synthesis {
 // Flag - This flag id used to distinguish types of dictionary entries.
 #[derive(Debug, Clone, PartialEq)]
 #[allow(dead_code)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Flag {
+    #[cfg_attr(feature = "serde", serde(rename = "R"))]
     Simple,	// A simple root (morpheme). Eg. 'muzik'
+    #[cfg_attr(feature = "serde", serde(rename = "K"))]
     Compound,	// A compound word. Eg. 'muzik.il'
+    #[cfg_attr(feature = "serde", serde(rename = "X"))]
     Exclude,	// Exclude from dictionary. For reference only.
     Separator,	// Flags a separator between morphemes. Eg. 'fingr.o.montr.i'
 }
 
 impl Flag {
+    // Unknown codes default to Flag::Exclude. See parse().
     pub fn new(s: &str) -> Flag {
+        return Flag::parse(s).unwrap_or(Flag::Exclude);
+    }
+
+    // Returns None if the code is unknown.
+    pub fn parse(s: &str) -> Option<Flag> {
         match s {
-            "R" => Flag::Simple,
-            "K" => Flag::Compound,
-            _ => Flag::Exclude,
+            "R" => Some(Flag::Simple),
+            "K" => Some(Flag::Compound),
+            "X" => Some(Flag::Exclude),
+            _ => None,
         }
     }
 }
@@ -498,8 +588,10 @@ impl Flag {
 
 // Define a dictionary entry.
 #[derive(Debug, Clone)]
+#[allow(dead_code)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Entry {
-    pub word: String,
+    pub word: String,			// as in the data, with periods between morphemes, eg. 'muzik.il'
     pub length: usize,
     pub capitalization: Capitalization,
     pub part_of_speech: POS,
@@ -515,9 +607,9 @@ pub struct Entry {
 impl Entry {	// A dictionary entry
 
     /* new()
-     * Assumes that the items in 'fields' are valid.
+     * Assumes that 'fields' has 9 items, and a numeric rarity (see check_row() in vortaro.rs).
      * Params: vector of dictionary data (strings)
-     * Return: optional dictionary entry
+     * Return: optional dictionary entry, None for exclusions, single letters and unknown codes
      */
     pub fn new(fields: Vec<&str>) -> Option<Entry> {
 
@@ -538,15 +630,15 @@ impl Entry {	// A dictionary entry
         let entry = Entry {
             word: _word2.clone(),
             length: _length,
-            part_of_speech: POS::new(&_vortspeco),
+            part_of_speech: POS::parse(&_vortspeco)?,
             capitalization: Capitalization::new(&_word2),
-            meaning: Meaning::new(&_meaning),
-            transitivity: Transitivity::new(&_transitivity),
-            without_ending: WithoutEnding::new(&_without_ending),
-            with_ending: WithEnding::new(&_with_ending),
-            synthesis: Synthesis::new(&_synthesis),
+            meaning: Meaning::parse(&_meaning)?,
+            transitivity: Transitivity::parse(&_transitivity)?,
+            without_ending: WithoutEnding::parse(&_without_ending)?,
+            with_ending: WithEnding::parse(&_with_ending)?,
+            synthesis: Synthesis::parse(&_synthesis)?,
             rarity: _rarity,
-            flag: Flag::new(&_flag),
+            flag: Flag::parse(&_flag)?,
          };
 
         // println!("____ {}", _synthesis);
@@ -563,7 +655,7 @@ impl Entry {	// A dictionary entry
      */
     pub fn empty() -> Entry {
 
-        let entry = Entry {
+        return Entry {
             word: String::new(),
             length: 0,
             part_of_speech: POS::Substantive,
@@ -576,7 +668,6 @@ impl Entry {	// A dictionary entry
             rarity: 0,
             flag: Flag::Simple,
         };
-        entry
 
     }  // pub fn empty
 
@@ -601,7 +692,7 @@ impl Entry {	// A dictionary entry
         };
 
         let entry = Entry {
-            word: format!("{}", separator),
+            word: separator.to_string(),
             length: 1,
             part_of_speech: pos,
             capitalization: Capitalization::Miniscule,
@@ -618,13 +709,98 @@ impl Entry {	// A dictionary entry
 
     }  // pub fn new_separator
 
+
+    /* new_country_suffix()
+     *
+     * This function creates an entry for the suffix -i, which forms the names
+     * of countries from ethnic roots. For example, 'Meksik.i.o', 'Angl.i.o'. It is
+     * not in the dictionary, because morphemes of one letter are excluded, and
+     * because 'io' is a pronoun.
+     *
+     * Return: dictionary entry for country suffix
+     */
+    pub fn new_country_suffix() -> Entry {
+
+        return Entry {
+            word: String::from("i"),
+            length: 1,
+            part_of_speech: POS::Substantive,
+            capitalization: Capitalization::Miniscule,
+            meaning: Meaning::Lando,
+            transitivity: Transitivity::Intransitive,
+            without_ending: WithoutEnding::No,
+            with_ending: WithEnding::Yes,
+            synthesis: Synthesis::Suffix,
+            rarity: 2,
+            flag: Flag::Simple,
+        };
+
+    }  // pub fn new_country_suffix
+
+
+    /* new_root()
+     *
+     * This function creates an entry for a word which is added at runtime, for
+     * example by SpellChecker::add_word(). Substantives, verbs, adjectives and adverbs
+     * take grammatical endings. Other parts of speech stand alone. A word which is
+     * divided by periods, eg. 'kamarad.an', is a compound.
+     *
+     * Params: word, part of speech, transitivity
+     * Return: dictionary entry
+     */
+    pub fn new_root(word: &str, pos: POS, transitivity: Transitivity) -> Entry {
+
+        let word = x_to_accent(word);
+        let takes_ending = matches!(pos,
+            POS::Substantive | POS::Verb | POS::Adjective |
+            POS::Adverb | POS::SubstantiveVerb);
+
+        let entry = Entry {
+            word: word.clone(),
+            length: word.chars().count(),
+            part_of_speech: pos,
+            capitalization: Capitalization::new(&word),
+            meaning: Meaning::NeKonata,
+            transitivity,
+            without_ending: if takes_ending { WithoutEnding::No } else { WithoutEnding::Yes },
+            with_ending: if takes_ending { WithEnding::Yes } else { WithEnding::No },
+            synthesis: Synthesis::UnLimited,
+            rarity: 2,
+            flag: if word.contains('.') { Flag::Compound } else { Flag::Simple },
+        };
+        entry
+
+    }  // pub fn new_root
+
 }  // end of impl Entry
 
 
+/*
+ * make_key
+ *
+ * Makes a dictionary key from a morpheme, by converting x-system letters
+ * to accented letters, removing periods and converting to lower case.
+ * Abbreviations which end in a period, such as 't.e.', keep their periods,
+ * so that they do not collide with other words (te = tea).
+ *
+ * Params:
+ *    morpheme, eg. 'ekzerc.ar'
+ *    part of speech
+ * Return:
+ *    key, eg. 'ekzercar'
+ */
+pub fn make_key(morpheme: &str, part_of_speech: POS) -> String {
+    let word = x_to_accent(morpheme);
+    if part_of_speech == POS::Abbreviation && word.ends_with('.') {
+        return word.to_lowercase();
+    }
+    return word.replace(".", "").to_lowercase();
+}
+
 
 // Case, nominative or accusative
-/*
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Case {
     Nominative,   // arbo
     Accusative,   // arbon
@@ -634,18 +810,16 @@ pub enum Case {
 // Otherwise assume it's nominative.
 impl Case {
     pub fn new(s: &str) -> Case {
-        if  s.len() < 1 { return Case::Nominative; }  // default
-        let last = s.chars().last().unwrap();
-        match last {
-            'n' => Case::Accusative,
-            'N' => Case::Accusative,
+        match s.chars().last() {
+            Some('n') | Some('N') => Case::Accusative,
             _ => Case::Nominative,
         }
     }
 }
 
 // Plurality: singular or plural.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Plurality {
     Singular,  // arbo
     Plural,  // arboj
@@ -659,22 +833,14 @@ pub enum Plurality {
 // arbojn - Plural
 impl Plurality {
     pub fn new(s: &str) -> Plurality {
-        if  s.len() < 2 { return Plurality::Singular; }
         let mut iter = s.chars().rev();
-        let last = iter.next().unwrap();
-        let _second_last = iter.next().unwrap();
-        if last == 'j' || last == 'J' { return Plurality::Plural; }
-        else if last == 'n' || last == 'N' {
-            match _second_last {
-                'j' => Plurality::Plural,
-                'J' => Plurality::Plural,
-                _ => Plurality::Singular,
-            }
-        }
-        else {
-            Plurality::Singular
+        let last = iter.next();
+        let second_last = iter.next();
+        match (second_last, last) {
+            (_, Some('j')) | (_, Some('J')) => Plurality::Plural,
+            (Some('j'), Some('n')) | (Some('j'), Some('N')) |
+            (Some('J'), Some('n')) | (Some('J'), Some('N')) => Plurality::Plural,
+            _ => Plurality::Singular,
         }
     }
 }
-
-*/
@@ -0,0 +1,146 @@
+// error_span - This module finds the positions of misspelled words in a text.
+// Klivo 2020-05-25
+
+use std::collections::HashMap;
+
+use super::vortaro::*;
+use super::check_word::*;
+use super::tokenizer::*;
+use super::suggest::*;
+
+// The maximum number of suggestions for each diagnostic.
+const MAX_SUGGESTIONS: usize = 5;
+
+/*
+ * ErrorSpan
+ * A misspelled word, and its position in the text.
+ * Fields:
+ *     'word' is the misspelled word.
+ *     'offset' is its byte offset in the text.
+ *     'line' is its line number, counting from 1.
+ *     'column' is its column in characters, counting from 1.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorSpan {
+    pub word: String,
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/*
+ * find_errors()
+ *
+ * Checks the spelling of each word in a text, and returns the position of
+ * each misspelled word. Repeated misspellings are all reported.
+ *
+ * Params:
+ *    text
+ *    dictionary
+ * Return:
+ *    list of misspelled words, with their positions
+ */
+pub fn find_errors(text: &str, dictionary: &Dictionary) -> Vec<ErrorSpan> {
+    return find_errors_with_options(text, dictionary, &Options::default());
+}
+
+
+/*
+ * find_errors_with_options()
+ *
+ * Like find_errors(), but the words are checked according to the given options.
+ *
+ * Params:
+ *    text
+ *    dictionary
+ *    options - settings for analysis, eg. strict_caps
+ * Return:
+ *    list of misspelled words, with their positions
+ */
+pub fn find_errors_with_options(text: &str, dictionary: &Dictionary,
+                                options: &Options) -> Vec<ErrorSpan> {
+
+    let mut errors = vec![];
+    let mut line = 1;
+    let mut line_start = 0;   // byte offset of the current line
+    let mut scanned = 0;      // byte offset up to which newlines have been counted
+
+    for token in tokenize(text) {
+        if !token.is_word { continue; }
+        for (i, ch) in text[scanned..token.offset].char_indices() {
+            if ch == '\n' {
+                line += 1;
+                line_start = scanned + i + 1;
+            }
+        }
+        scanned = token.offset;
+        if !check_word_with_options(token.text, dictionary, options).valid {
+            let column = text[line_start..token.offset].chars().count() + 1;
+            errors.push(ErrorSpan {
+                word: String::from(token.text),
+                offset: token.offset,
+                line,
+                column,
+            });
+        }
+    }
+    return errors;
+
+}  // find_errors_with_options()
+
+
+/*
+ * Diagnostic
+ * A misspelled word in a document, for an editor.
+ * Fields:
+ *     'start' is the byte offset of the word in the text.
+ *     'end' is the byte offset after the word.
+ *     'word' is the misspelled word.
+ *     'suggestions' are possible corrections, best first.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub start: usize,
+    pub end: usize,
+    pub word: String,
+    pub suggestions: Vec<String>,
+}
+
+/*
+ * diagnostics()
+ *
+ * Checks the spelling of each word in a document, and returns a diagnostic,
+ * with suggestions, for each misspelled word. Numbers, abbreviations with
+ * periods (eg. 'k.t.p.') and acronyms in capitals (eg. 'NASA') are not reported.
+ * Suggestions for a repeated misspelling are only calculated once.
+ *
+ * Params:
+ *    text
+ *    dictionary
+ * Return:
+ *    list of diagnostics, in order
+ */
+pub fn diagnostics(text: &str, dictionary: &Dictionary) -> Vec<Diagnostic> {
+
+    let mut diagnostics = vec![];
+    let mut cache: HashMap<&str, Vec<String>> = HashMap::new();
+
+    for token in tokenize(text) {
+        if !token.is_word { continue; }
+        let word = token.text;
+        if word.contains(|ch: char| ch.is_ascii_digit() || ch == '.') { continue; }
+        if word.chars().count() > 1 && !word.chars().any(|ch| ch.is_lowercase()) { continue; }
+        if is_valid(word, dictionary) { continue; }
+        let suggestions = cache.entry(word)
+                               .or_insert_with(|| suggest(word, dictionary, MAX_SUGGESTIONS))
+                               .clone();
+        diagnostics.push(Diagnostic {
+            start: token.offset,
+            end: token.offset + word.len(),
+            word: String::from(word),
+            suggestions,
+        });
+    }
+    return diagnostics;
+
+}  // diagnostics()
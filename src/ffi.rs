@@ -0,0 +1,129 @@
+// ffi - This module has a C interface to the spell checker, for embedding
+// Literumilo in programs written in other languages, such as C++.
+// It is compiled with the 'ffi' feature. To make a shared library:
+//     cargo rustc --lib --release --features ffi --crate-type cdylib
+//
+// Strings are UTF-8 and null terminated. Strings returned by Literumilo must be
+// freed with literumilo_free_string(), and the spell checker with literumilo_free().
+// Klivo 2020-05-28
+
+// The safety requirements are described in the comment above each function.
+#![allow(clippy::missing_safety_doc)]
+
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+
+use super::spell_checker::*;
+
+/*
+ * to_str()
+ *
+ * Converts a C string to a Rust string slice.
+ *
+ * Params:
+ *    pointer to a null terminated string
+ * Return:
+ *    string slice, or None if the pointer is null or the string is not UTF-8
+ */
+unsafe fn to_str<'a>(s: *const c_char) -> Option<&'a str> {
+    if s.is_null() { return None; }
+    return CStr::from_ptr(s).to_str().ok();
+}
+
+/*
+ * literumilo_new()
+ *
+ * Makes a spell checker with the embedded dictionary.
+ *
+ * Return:
+ *    pointer to the spell checker, or null if the embedded dictionary is invalid
+ */
+#[no_mangle]
+pub extern "C" fn literumilo_new() -> *mut SpellChecker {
+    return match SpellChecker::from_embedded() {
+        Ok(checker) => Box::into_raw(Box::new(checker)),
+        Err(_) => std::ptr::null_mut(),
+    };
+}
+
+/*
+ * literumilo_check()
+ *
+ * Checks the spelling of a word.
+ *
+ * Safety: 'checker' must come from literumilo_new(), and 'word' must be
+ * null or a null terminated string.
+ *
+ * Params:
+ *    spell checker
+ *    word
+ * Return:
+ *    true if the word is valid, false if it is not, or if a parameter is invalid
+ */
+#[no_mangle]
+pub unsafe extern "C" fn literumilo_check(checker: *const SpellChecker, word: *const c_char) -> bool {
+    if checker.is_null() { return false; }
+    if let Some(word) = to_str(word) {
+        return (*checker).check(word).valid;
+    }
+    return false;
+}
+
+/*
+ * literumilo_analyze()
+ *
+ * Divides the words of a text into morphemes, eg. 'La elefantoj.' -> 'La elefant.oj.'
+ *
+ * Safety: 'checker' must come from literumilo_new(), and 'text' must be
+ * null or a null terminated string.
+ *
+ * Params:
+ *    spell checker
+ *    text
+ * Return:
+ *    analyzed text, to be freed with literumilo_free_string(),
+ *    or null if a parameter is invalid
+ */
+#[no_mangle]
+pub unsafe extern "C" fn literumilo_analyze(checker: *const SpellChecker, text: *const c_char) -> *mut c_char {
+    if checker.is_null() { return ptr::null_mut(); }
+    if let Some(text) = to_str(text) {
+        if let Ok(analyzed) = CString::new((*checker).analyze_text(text)) {
+            return analyzed.into_raw();
+        }
+    }
+    return ptr::null_mut();
+}
+
+/*
+ * literumilo_free_string()
+ *
+ * Frees a string returned by literumilo_analyze(). Null is ignored.
+ *
+ * Safety: 's' must come from literumilo_analyze(), and must be freed only once.
+ *
+ * Params:
+ *    string
+ */
+#[no_mangle]
+pub unsafe extern "C" fn literumilo_free_string(s: *mut c_char) {
+    if s.is_null() { return; }
+    drop(CString::from_raw(s));
+}
+
+/*
+ * literumilo_free()
+ *
+ * Frees a spell checker. Null is ignored.
+ *
+ * Safety: 'checker' must come from literumilo_new(), and must be freed only once.
+ *
+ * Params:
+ *    spell checker
+ */
+#[no_mangle]
+pub unsafe extern "C" fn literumilo_free(checker: *mut SpellChecker) {
+    if checker.is_null() { return; }
+    drop(Box::from_raw(checker));
+}
@@ -0,0 +1,379 @@
+// file_report - This module analyzes a whole text, and returns the results
+// as data, rather than printing them, so that a program with a graphical
+// interface can display them.
+// Klivo 2020-05-29
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::ops::Range;
+use std::thread;
+
+use super::entry::*;
+use super::vortaro::*;
+use super::check_word::*;
+use super::tokenizer::*;
+
+/*
+ * FileReport
+ * The result of analyzing a text.
+ * Fields:
+ *     'analyzed_text' is the text with each known word divided into morphemes,
+ *     eg. 'La elefant.oj.' Only in morpheme mode, otherwise None.
+ *     'misspelled' has the misspelled words, in the order in which they first
+ *     appear. Repeated misspellings are listed once.
+ *     'unusual' has valid words whose first morpheme is rarer than the maximum
+ *     rarity, with their rarity. (See ReportOptions.)
+ *     'results' has each word of the text with its analysis, if requested.
+ *     'word_count' counts every word, 'distinct_count' counts different words
+ *     (ignoring case), and 'misspelled_count' counts misspelled words, including
+ *     repetitions.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct FileReport {
+    pub analyzed_text: Option<String>,
+    pub misspelled: Vec<String>,
+    pub unusual: Vec<(String, usize)>,
+    pub results: Vec<(String, AnalysisResult)>,
+    pub word_count: usize,
+    pub distinct_count: usize,
+    pub misspelled_count: usize,
+}
+
+/*
+ * ReportOptions
+ * Settings for analyze_text_with_options().
+ * Fields:
+ *     'check' has the settings for check_word_with_options(), eg. strict_caps.
+ *     'ignore_proper' does not count capitalized words within a sentence (names)
+ *     as misspelled. (See is_proper_noun().)
+ *     'max_rarity' reports valid words whose first morpheme is rarer than this.
+ *     'tagged' follows each morpheme of the analyzed text with its role. (See tagged_form().)
+ *     'results' keeps the analysis of every word in the report.
+ *     'jobs' is the number of threads which analyze the text.
+ */
+#[derive(Debug, Clone)]
+pub struct ReportOptions {
+    pub check: Options,
+    pub ignore_proper: bool,
+    pub max_rarity: Option<usize>,
+    pub tagged: bool,
+    pub results: bool,
+    pub jobs: usize,
+}
+
+impl Default for ReportOptions {
+    fn default() -> ReportOptions {
+        return ReportOptions { check: Options::default(), ignore_proper: false,
+                               max_rarity: None, tagged: false, results: false, jobs: 1 };
+    }
+}
+
+/*
+ * analyze_text()
+ *
+ * Analyzes a text. In morpheme mode, the text is divided into morphemes.
+ * The misspelled words are collected in either mode.
+ *
+ * Params:
+ *    text
+ *    dictionary
+ *    morpheme mode
+ * Return:
+ *    report
+ */
+pub fn analyze_text(text: &str, dictionary: &Dictionary, morpheme_mode: bool) -> FileReport {
+    return analyze_text_with_options(text, dictionary, morpheme_mode, &ReportOptions::default());
+}  // analyze_text()
+
+
+/*
+ * analyze_text_with_options()
+ *
+ * Like analyze_text(), but according to the given options. With several jobs,
+ * the text is divided into chunks of lines, which are analyzed in parallel.
+ * Words do not cross lines, so the chunks can be analyzed separately.
+ *
+ * Params:
+ *    text
+ *    dictionary
+ *    morpheme mode
+ *    options
+ * Return:
+ *    report
+ */
+pub fn analyze_text_with_options(text: &str, dictionary: &Dictionary, morpheme_mode: bool,
+                                 options: &ReportOptions) -> FileReport {
+
+    let chunks = split_lines(text, options.jobs);
+    let chunk_reports: Vec<(FileReport, HashSet<String>)> = thread::scope(|scope| {
+        let handles: Vec<_> = chunks.into_iter().map(|chunk| {
+            scope.spawn(move || analyze_chunk(text, chunk, dictionary, morpheme_mode, options))
+        }).collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+
+    // Join the chunk reports. Words which are repeated in several chunks are listed once.
+    let mut report = FileReport::default();
+    let mut analyzed = String::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut distinct: HashSet<String> = HashSet::new();
+    for (chunk_report, chunk_distinct) in chunk_reports {
+        if let Some(chunk_text) = chunk_report.analyzed_text { analyzed.push_str(&chunk_text); }
+        for word in chunk_report.misspelled {
+            if seen.insert(word.clone()) { report.misspelled.push(word); }
+        }
+        for (word, rarity) in chunk_report.unusual {
+            if seen.insert(word.clone()) { report.unusual.push((word, rarity)); }
+        }
+        report.results.extend(chunk_report.results);
+        report.word_count += chunk_report.word_count;
+        report.misspelled_count += chunk_report.misspelled_count;
+        distinct.extend(chunk_distinct);
+    }
+    report.distinct_count = distinct.len();
+    if morpheme_mode { report.analyzed_text = Some(analyzed); }
+    return report;
+
+}  // analyze_text_with_options()
+
+
+/*
+ * analyze_chunk()
+ *
+ * Analyzes the words of a part of a text. Repeated words, such as 'la' and
+ * 'kaj', are analyzed only once. The whole text is passed, so that a word at
+ * the start of the part is seen with the text which precedes it (see is_proper_noun()).
+ *
+ * Params:
+ *     whole text
+ *     byte range of the part
+ *     dictionary
+ *     morpheme mode
+ *     options
+ * Return:
+ *     report of the part, and the set of its words in lower case
+ */
+fn analyze_chunk(text: &str, chunk: Range<usize>, dictionary: &Dictionary, morpheme_mode: bool,
+                 options: &ReportOptions) -> (FileReport, HashSet<String>) {
+
+    let mut report = FileReport::default();
+    let mut analyzed = String::new();
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut distinct: HashSet<String> = HashSet::new();
+    let mut cache: HashMap<&str, AnalysisResult> = HashMap::new();
+    let start = chunk.start;
+
+    for token in tokenize(&text[chunk]) {
+        if !token.is_word {
+            if morpheme_mode { analyzed.push_str(token.text); }
+            continue;
+        }
+        let word = token.text;
+        let result = cache.entry(word).or_insert_with(|| {
+            check_word_with_options(word, dictionary, &options.check)
+        });
+        let mut valid = result.valid;
+        if !valid && options.ignore_proper {
+            valid = is_proper_noun(word, &text[..start + token.offset]);
+        }
+        report.word_count += 1;
+        distinct.insert(word.to_lowercase());
+        if morpheme_mode {
+            if options.tagged { analyzed.push_str(&tagged_form(result)); }
+            else { analyzed.push_str(&result.word); }
+        }
+        if options.results {
+            report.results.push((String::from(word), result.clone()));
+        }
+        if !valid {
+            report.misspelled_count += 1;
+            if seen.insert(word) { report.misspelled.push(String::from(word)); }
+        }
+        else if let (Some(max_rarity), Some(root)) = (options.max_rarity, result.morphemes.first()) {
+            if root.rarity > max_rarity && seen.insert(word) {
+                report.unusual.push((String::from(word), root.rarity));
+            }
+        }
+    }
+    if morpheme_mode { report.analyzed_text = Some(analyzed); }
+    return (report, distinct);
+
+}  // analyze_chunk()
+
+
+/*
+ * split_lines()
+ *
+ * Divides a text into about 'count' chunks of whole lines.
+ *
+ * Params:
+ *     text
+ *     number of chunks
+ * Return:
+ *     byte ranges of the chunks
+ */
+fn split_lines(text: &str, count: usize) -> Vec<Range<usize>> {
+    let mut chunks = vec![];
+    if count < 2 { chunks.push(0..text.len()); return chunks; }
+    let size = text.len() / count + 1;
+    let mut start = 0;
+    while start < text.len() {
+        // The split point must not fall within a multi-byte letter, eg. 'ŝ'.
+        let mut split = (start + size).min(text.len());
+        while !text.is_char_boundary(split) { split += 1; }
+        let end = match text[split..].find('\n') {
+            Some(newline) => split + newline + 1,
+            None => text.len(),
+        };
+        chunks.push(start..end);
+        start = end;
+    }
+    return chunks;
+}  // split_lines()
+
+
+/*
+ * sentence_start()
+ *
+ * Determines whether a word begins a sentence, from the text which precedes it.
+ * Spaces, quotation marks and other punctuation are skipped back to the end of
+ * the previous word. If that word ends a sentence (. ! ? …), or if there is no
+ * previous word, the word begins a sentence.
+ *
+ * Params:
+ *     text which precedes the word
+ * Return:
+ *     true if the word begins a sentence
+ */
+fn sentence_start(preceding: &str) -> bool {
+    let preceding = preceding.trim_end_matches(|ch: char| {
+        !is_word_char!(ch) && !ch.is_ascii_digit() && !".!?…".contains(ch)
+    });
+    return preceding.is_empty() || preceding.ends_with(['.', '!', '?', '…']);
+}  // sentence_start()
+
+
+/*
+ * is_proper_noun()
+ *
+ * Determines whether a word which is not in the dictionary is probably a name,
+ * that is, whether it is capitalized (eg. 'Zamenhof') and does not begin a sentence.
+ *
+ * Params:
+ *     word
+ *     text which precedes the word
+ * Return:
+ *     true for a probable name
+ */
+pub fn is_proper_noun(word: &str, preceding: &str) -> bool {
+    return matches!(Capitalization::new(word), Capitalization::Majuscule) &&
+           !sentence_start(preceding);
+}  // is_proper_noun()
+
+
+/*
+ * tagged_form()
+ *
+ * Follows each morpheme of an analyzed word with its role in the word, in angle
+ * brackets, eg. 'for⟨PREF⟩.ig⟨SUF⟩.it⟨PART⟩.aj⟨ENDING:Adjective⟩'. Roots are tagged
+ * with their part of speech, eg. 'hund⟨ROOT:Substantive⟩.o⟨ENDING:Substantive⟩', and
+ * separator vowels with 'SEP'. Abbreviations, such as 'k.t.p.', are tagged as a whole.
+ * Words which have no morphemes from the dictionary, such as 'kiu', are not tagged.
+ *
+ * Params:
+ *     analysis result
+ * Return:
+ *     tagged word
+ */
+pub fn tagged_form(result: &AnalysisResult) -> String {
+    if !result.valid || result.morphemes.is_empty() {
+        return result.word.clone();
+    }
+    if result.morphemes.iter().any(|m| m.part_of_speech == POS::Abbreviation) {
+        return format!("{}⟨ABBR⟩", result.word);
+    }
+    let last = result.morphemes.len() - 1;
+    let (mut tagged, rest) = result.format_morphemes(|tagged, index, morpheme, text| {
+        tagged.push_str(text);
+        tagged.push_str(&format!("⟨{}⟩", morpheme_tag(morpheme, index, last)));
+    });
+    if rest.is_empty() { return tagged; }
+    tagged.push_str(&rest);
+    match result.part_of_speech {
+        Some(pos) => tagged.push_str(&format!("⟨ENDING:{:?}⟩", pos)),
+        None => tagged.push_str("⟨ENDING⟩"),    // vi.n
+    }
+    return tagged;
+}  // tagged_form()
+
+// Returns the tag of a morpheme. A prefix is only tagged 'PREF' when something
+// follows it, and a suffix or participle ending when something precedes it.
+// Otherwise, eg. the 'ig' of 'ig.i', the morpheme is a root.
+fn morpheme_tag(morpheme: &MorphemeInfo, index: usize, last: usize) -> String {
+    if morpheme.is_separator { return String::from("SEP"); }
+    return match morpheme.synthesis {
+        Synthesis::Prefix if index < last => String::from("PREF"),
+        Synthesis::Suffix if index > 0 => String::from("SUF"),
+        Synthesis::Participle if index > 0 => String::from("PART"),
+        _ => format!("ROOT:{:?}", morpheme.part_of_speech),
+    };
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use super::super::vortaro::load_precompiled;
+
+    #[test]
+    fn split_lines_multibyte() {
+        let text = "Ŝi manĝas ĉiutage ĵaŭde.\nĈu ŝi ŝatas ĥorojn?\n".repeat(50);
+        for count in 2..17 {
+            let chunks: Vec<&str> = split_lines(&text, count).into_iter()
+                .map(|chunk| &text[chunk]).collect();
+            assert!(chunks.len() > 1);
+            assert_eq!(chunks.concat(), text);
+            for chunk in &chunks { assert!(chunk.ends_with('\n')); }
+        }
+    }
+
+    // The report does not depend on the number of jobs.
+    #[test]
+    fn reports_with_jobs() {
+        let text = "La hundoj kaj la katoj manĝas.\nZorbak hundoi hundoj.\n".repeat(100);
+        let dictionary = load_precompiled().unwrap();
+        for morpheme_mode in [false, true].iter() {
+            let one = analyze_text(&text, &dictionary, *morpheme_mode);
+            for jobs in [2, 4, 8].iter() {
+                let options = ReportOptions { jobs: *jobs, ..ReportOptions::default() };
+                let report = analyze_text_with_options(&text, &dictionary, *morpheme_mode,
+                                                       &options);
+                assert_eq!(report.analyzed_text, one.analyzed_text);
+                assert_eq!(report.misspelled, one.misspelled);
+                assert_eq!(report.word_count, one.word_count);
+                assert_eq!(report.distinct_count, one.distinct_count);
+                assert_eq!(report.misspelled_count, one.misspelled_count);
+            }
+        }
+        let one = analyze_text(&text, &dictionary, false);
+        assert_eq!(one.misspelled, vec!["Zorbak", "hundoi"]);
+        assert_eq!(one.word_count, 900);
+        assert_eq!(one.distinct_count, 7);
+        assert_eq!(one.misspelled_count, 200);
+    }
+
+    #[test]
+    fn tagged_morphemes() {
+        let dictionary = load_precompiled().unwrap();
+        let tag = |word: &str| tagged_form(&check_word(word, &dictionary));
+        assert_eq!(tag("forigitaj"), "for⟨PREF⟩.ig⟨SUF⟩.it⟨PART⟩.aj⟨ENDING:Adjective⟩");
+        assert_eq!(tag("naskotago"),
+                   "nask⟨ROOT:Verb⟩.o⟨SEP⟩.tag⟨ROOT:Substantive⟩.o⟨ENDING:Substantive⟩");
+        assert_eq!(tag("igi"), "ig⟨ROOT:Verb⟩.i⟨ENDING:Verb⟩");
+        assert_eq!(tag("vin"), "vi⟨ROOT:Pronoun⟩.n⟨ENDING⟩");
+        assert_eq!(tag("k.t.p."), "k.t.p.⟨ABBR⟩");
+        assert_eq!(tag("hundoi"), "hundoi");
+    }
+
+}  // tests
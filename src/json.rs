@@ -0,0 +1,126 @@
+// json - This module formats analysis results as JSON.
+// A small serializer is used, so that the program has no dependencies.
+// Klivo 2020-05-22
+
+use literumilo::*;
+
+/*
+ * escape()
+ *
+ * Escapes a string for inclusion in JSON, and encloses it in quotes.
+ *
+ * Params:
+ *    string to escape
+ * Return:
+ *    quoted JSON string
+ */
+fn escape(s: &str) -> String {
+    let mut escaped = String::from("\"");
+    for ch in s.chars() {
+        match ch {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    return escaped;
+}
+
+/*
+ * optional()
+ *
+ * Formats an optional value as a JSON string, or null.
+ *
+ * Params:
+ *    optional value
+ * Return:
+ *    JSON value
+ */
+fn optional(value: Option<String>) -> String {
+    return match value {
+        Some(s) => escape(&s),
+        None => String::from("null"),
+    };
+}
+
+/*
+ * reason_to_json()
+ *
+ * Formats the reason why a word is invalid as a JSON object, eg.:
+ * { "kind": "BadSynthesis", "index": 1 }
+ *
+ * Params:
+ *    optional reason
+ * Return:
+ *    JSON object, or null
+ */
+fn reason_to_json(reason: Option<Invalidity>) -> String {
+    let kind = match reason {
+        None => return String::from("null"),
+        Some(Invalidity::BadSynthesis { index }) => {
+            return format!("{{ \"kind\": \"BadSynthesis\", \"index\": {} }}", index);
+        },
+        Some(Invalidity::NoEnding) => "NoEnding",
+        Some(Invalidity::RootNotFound) => "RootNotFound",
+        Some(Invalidity::TooManyMorphemes) => "TooManyMorphemes",
+        Some(Invalidity::Uncapitalized) => "Uncapitalized",
+    };
+    return format!("{{ \"kind\": {} }}", escape(kind));
+}
+
+/*
+ * result_to_json()
+ *
+ * Formats the analysis of one word as a JSON object, eg.:
+ * { "original": "forigitaj", "analyzed": "for.ig.it.aj", "valid": true, ... }
+ *
+ * Params:
+ *    original word
+ *    analysis result
+ * Return:
+ *    JSON object
+ */
+pub fn result_to_json(original: &str, result: &AnalysisResult) -> String {
+
+    let morphemes: Vec<String> = result.morphemes.iter().map(|m| {
+        format!("{{ \"morpheme\": {}, \"part_of_speech\": {}, \"meaning\": {}, \"transitivity\": {}, \"rarity\": {}, \"separator\": {} }}",
+                escape(&m.word),
+                escape(&format!("{:?}", m.part_of_speech)),
+                escape(&format!("{:?}", m.meaning)),
+                escape(&format!("{:?}", m.transitivity)),
+                m.rarity, m.is_separator)
+    }).collect();
+
+    return format!("{{ \"original\": {}, \"analyzed\": {}, \"valid\": {}, \
+                    \"part_of_speech\": {}, \"ending\": {}, \"accusative\": {}, \
+                    \"reason\": {}, \"rarity\": {}, \"morphemes\": [{}] }}",
+                   escape(original),
+                   escape(&result.word),
+                   result.valid,
+                   optional(result.part_of_speech.map(|pos| format!("{:?}", pos))),
+                   optional(result.ending.clone()),
+                   result.accusative,
+                   reason_to_json(result.reason),
+                   result.rarity.map_or(String::from("null"), |rarity| rarity.to_string()),
+                   morphemes.join(", "));
+}
+
+/*
+ * json_array()
+ *
+ * Joins JSON objects into an array, one object per line.
+ *
+ * Params:
+ *    JSON objects
+ * Return:
+ *    JSON array
+ */
+pub fn json_array(objects: &[String]) -> String {
+    if objects.is_empty() { return String::from("[]"); }
+    return format!("[\n  {}\n]", objects.join(",\n  "));
+}
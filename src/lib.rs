@@ -1,56 +1,333 @@
 // This library has macros and functions for dealing with Esperanto's accented characters.
+// It also exports the spell checker and morphological analyzer, and the dictionary.
 // Klivo (indriko@yahoo.com) 2020-05-08
 
 #[macro_use]
 mod macros;
 
+mod x_system;
+mod suffix;
+mod entry;
+mod suggest;
+mod ending;
+mod vortaro;
+mod check_word;
+mod morpheme_list;
+mod scan_morphemes;
+mod tokenizer;
+mod spell_checker;
+mod error_span;
+mod trie;
+mod embedded;
+mod file_report;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+pub use x_system::x_to_accent;
+pub use entry::{Entry, POS, Meaning, Transitivity, Capitalization,
+                WithoutEnding, WithEnding, Synthesis, Flag, Case, Plurality, make_key};
+pub use ending::{grammatical_features, Features};
+pub use check_word::{check_word, check_word_with_options, check_word_normalized, check_many,
+                     is_valid, analyze_all, missing_root, explain_synthesis, default_exceptions,
+                     Options, AnalysisResult, MorphemeInfo, Invalidity};
+pub use vortaro::{make_dictionary, load_precompiled, add_words, lint_dictionary,
+                  Dictionary, DictError, DictErrorKind};
+pub use morpheme_list::MAX_MORPHEMES;
+pub use suggest::{suggest, suggest_hats};
+pub use tokenizer::{tokenize, Token};
+pub use spell_checker::SpellChecker;
+pub use error_span::{find_errors, find_errors_with_options, ErrorSpan, diagnostics, Diagnostic};
+pub use embedded::{analyze_string, check_one};
+pub use file_report::{analyze_text, analyze_text_with_options, FileReport, ReportOptions,
+                      is_proper_noun, tagged_form};
+
+use std::collections::HashMap;
+
+// The dictionary and the spell checker are immutable while words are checked,
+// so they can be shared between threads, for example in an Arc.
+fn _assert_send_sync<T: Send + Sync>() {}
+const _: fn() = || {
+    _assert_send_sync::<Entry>();
+    _assert_send_sync::<Dictionary>();
+    _assert_send_sync::<SpellChecker>();
+    _assert_send_sync::<AnalysisResult>();
+};
+
 /*
- * x_to_accent()
+ * accent_to_x()
  *
- * Converts cx to ĉ, sx to ŝ, etc., for an entire string.
+ * Converts ĉ to cx, ŝ to sx, etc., for an entire string. This is the
+ * reverse of x_to_accent(). For capital letters, the x is capitalized
+ * if the following letter is also a capital: 'ĈIU' -> 'CXIU', 'Ĉiu' -> 'Cxiu'.
  *
  * Params:
  *    original word (&str)
  * Return:
  *    new word (String)
  */
-pub fn x_to_accent(word: &str) -> String {
+pub fn accent_to_x(word: &str) -> String {
 
     let word = word.chars().collect::<Vec<_>>();
     let length = word.len();
-    let mut _new_word = String::from("");
+    let mut new_word = String::from("");
 
-    let mut skip_x = false;   // For skipping over x.
     for i in 0..length {
-        if skip_x { skip_x = false; continue; }
         let ch1 = word[i];
-        if accepts_hat!(ch1) {
-            if i < (length - 1) {
-                let ch2 = word[i + 1];
-                if is_x!(ch2) {
-                    _new_word.push(accent_letter!(ch1));
-                    skip_x = true;
-                }
-                else {
-                    _new_word.push(ch1);
-                }
+        if has_hat!(ch1) {
+            let with_x = accent_letter_to_x!(ch1);
+            if ch1.is_uppercase() && i < (length - 1) && word[i + 1].is_uppercase() {
+                new_word.push_str(&with_x.to_uppercase());
             }
             else {
-                _new_word.push(ch1);
+                new_word.push_str(with_x);
             }
         }
         else {
-            _new_word.push(ch1);
+            new_word.push(ch1);
         }
     }  // for
 
-    return _new_word;
-}  // x_to_accent
+    return new_word;
+}  // accent_to_x
+
+
+/*
+ * compose_accents()
+ *
+ * Some editors write 'ĉ' as 'c' followed by a combining circumflex (U+0302),
+ * and 'ŭ' as 'u' followed by a combining breve (U+0306). This function converts
+ * these pairs to the precomposed letters of the dictionary (U+0109, U+016D, etc.).
+ * Other combining marks are unchanged.
+ *
+ * Params:
+ *    original word (&str)
+ * Return:
+ *    new word (String)
+ */
+pub fn compose_accents(word: &str) -> String {
+
+    let mut new_word = String::with_capacity(word.len());
+    let mut chars = word.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if let Some(&mark) = chars.peek() {
+            let is_u = ch == 'u' || ch == 'U';
+            let circumflex = mark == '\u{302}' && accepts_hat!(ch) && !is_u;
+            let breve = mark == '\u{306}' && is_u;
+            if circumflex || breve {
+                new_word.push(accent_letter!(ch));
+                chars.next();
+                continue;
+            }
+        }
+        new_word.push(ch);
+    }
+
+    return new_word;
+}  // compose_accents
+
+
+/*
+ * h_to_accent()
+ *
+ * Converts words written in the h-system (Zamenhof's orthography) to Unicode.
+ * The digraphs ch, gh, sh, jh and hh become ĉ, ĝ, ŝ, ĵ and ĥ. The h-system writes
+ * ŭ as a plain 'u', so the u of the diphthongs 'au' and 'eu' may become ŭ.
+ *
+ * The h-system is ambiguous. In 'flughaveno' (flug.haven.o), 'gh' is not ĝ.
+ * Therefore, a conversion is only accepted if the resulting word can be divided
+ * into morphemes (keys) which are found in the dictionary, followed by an optional
+ * grammatical ending. Conversions are tried from most to fewest. If no version of
+ * the word can be divided, the word is returned unchanged.
+ *
+ * Note: The division only tests for the presence of morphemes in the dictionary.
+ * It does not check synthesis, as check_word() does.
+ *
+ * Params:
+ *    original word (&str)
+ *    dictionary (keys are lower case morphemes)
+ * Return:
+ *    new word (String)
+ */
+pub fn h_to_accent<V>(word: &str, dictionary: &HashMap<String, V>) -> String {
+
+    const MAX_CANDIDATES: usize = 8;  // Limits the number of combinations (2^8).
+
+    let chars = word.chars().collect::<Vec<_>>();
+    let length = chars.len();
+
+    // Find the positions of possible digraphs.
+    let mut positions: Vec<usize> = vec![];
+    let mut i = 0;
+    while i + 1 < length {
+        let ch1 = chars[i];
+        let ch2 = chars[i + 1];
+        let lower1 = ch1.to_lowercase().next().unwrap_or(ch1);
+        let lower2 = ch2.to_lowercase().next().unwrap_or(ch2);
+        if lower1 != 'u' && accepts_hat!(lower1) && lower2 == 'h' {
+            positions.push(i);
+            i += 2;
+            continue;
+        }
+        if (lower1 == 'a' || lower1 == 'e') && lower2 == 'u' {
+            positions.push(i + 1);
+            i += 2;
+            continue;
+        }
+        i += 1;
+    }
+
+    if positions.is_empty() || positions.len() > MAX_CANDIDATES {
+        return String::from(word);
+    }
+
+    // Order the combinations from most conversions to fewest.
+    let number_of_positions = positions.len();
+    let mut combinations: Vec<u32> = (1..(1u32 << number_of_positions)).collect();
+    combinations.sort_by_key(|c| std::cmp::Reverse(c.count_ones()));
+
+    for combination in combinations {
+        let mut candidate = String::from("");
+        let mut i = 0;
+        while i < length {
+            let ch = chars[i];
+            if let Some(n) = positions.iter().position(|&p| p == i) {
+                if combination & (1 << n) != 0 {
+                    candidate.push(accent_letter!(ch));
+                    if ch != 'u' && ch != 'U' { i += 1; }  // Skip over the h.
+                    i += 1;
+                    continue;
+                }
+            }
+            candidate.push(ch);
+            i += 1;
+        }
+        let lower: Vec<char> = candidate.to_lowercase().chars().collect();
+        let mut failed = vec![false; lower.len()];
+        if divisible(&lower, 0, dictionary, &mut failed) { return candidate; }
+    }
+
+    return String::from(word);
+
+}  // h_to_accent
+
+
+/*
+ * divisible()
+ *
+ * Tests whether the rest of a word can be divided into dictionary keys,
+ * followed by an optional grammatical ending. Used by h_to_accent().
+ *
+ * Params:
+ *    characters of word (lower case)
+ *    start of the rest of the word
+ *    dictionary
+ *    positions which are known to fail (to avoid repeated work)
+ * Return:
+ *    true if divisible, false otherwise
+ */
+fn divisible<V>(chars: &[char], start: usize, dictionary: &HashMap<String, V>,
+                failed: &mut [bool]) -> bool {
+
+    let length = chars.len();
+    if start >= length { return true; }
+    if failed[start] { return false; }
+
+    let rest: String = chars[start..].iter().collect();
+    match &rest[..] {
+        "o" | "a" | "e" | "i" | "u" | "as" | "is" | "os" | "us" |
+        "oj" | "on" | "ojn" | "aj" | "an" | "ajn" | "en" => return true,
+        _ => (),
+    }
+
+    for end in (start + 2 ..= length).rev() {
+        let morpheme: String = chars[start..end].iter().collect();
+        if dictionary.contains_key(&morpheme) &&
+           divisible(chars, end, dictionary, failed) { return true; }
+    }
+
+    failed[start] = true;
+    return false;
+
+}  // divisible
 
 
-// remove_hyphens - Remove hyphens from string.
+/*
+ * strip_accents()
+ *
+ * Removes the 'hats' from Esperanto letters, for an entire string.
+ * Eg. 'ĉirkaŭ' becomes 'cirkau'. Unlike accent_to_x(), no x is added.
+ * Other characters, including non-Esperanto letters, are unchanged.
+ *
+ * Params:
+ *    original word (&str)
+ * Return:
+ *    new word (String)
+ */
+pub fn strip_accents(word: &str) -> String {
+    return word.chars().map(|ch| remove_hat!(ch)).collect();
+}
+
+
+// Orthography - The system used to write Esperanto's accented letters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Orthography {
+    Unicode,    // ĉirkaŭ
+    XSystem,    // cxirkaux
+    HSystem,    // chirkau
+    Plain,      // No special letters, eg. 'domo'.
+}
+
+/*
+ * detect_orthography()
+ *
+ * Determines which system a word uses to represent accented letters.
+ * A word which contains ĉ, ŭ, etc., is Unicode. A word which has an x after
+ * a letter which accepts a hat (cx, ux) is in the x-system. A word which has
+ * ch, gh, sh, jh or hh may be in the h-system. Note that the h-system is ambiguous;
+ * 'flughaveno' is reported as HSystem. Otherwise, the word is Plain.
+ *
+ * Params:
+ *    word (&str)
+ * Return:
+ *    orthography
+ */
+pub fn detect_orthography(word: &str) -> Orthography {
+
+    let chars = word.chars().collect::<Vec<_>>();
+    if chars.iter().any(|&ch| has_hat!(ch)) { return Orthography::Unicode; }
+
+    let mut h_system = false;
+    for pair in chars.windows(2) {
+        let ch1 = pair[0];
+        let ch2 = pair[1];
+        if accepts_hat!(ch1) && is_x!(ch2) { return Orthography::XSystem; }
+        if ch1 != 'u' && ch1 != 'U' && accepts_hat!(ch1) && (ch2 == 'h' || ch2 == 'H') {
+            h_system = true;
+        }
+    }
+
+    if h_system { return Orthography::HSystem; }
+    return Orthography::Plain;
+
+}  // detect_orthography
+
+
+// remove_hyphens - Remove hyphens from string: hyphen-minus, soft hyphen,
+// hyphen (U+2010) and non-breaking hyphen (U+2011). Dashes, such as the
+// en dash and em dash, are punctuation, and are not removed.
 pub fn remove_hyphens(word: &str) -> String {
-    return word.replace("-", "").replace("­", "");
+    return word.chars().filter(|&ch| !is_hyphen!(ch)).collect();
+}
+
+// normalize - Prepares a word for comparison with dictionary keys. Like the spell
+// checker, it composes combining accents, removes hyphens, and converts to lower case.
+// Unlike check_word(), it also converts x-system letters: 'Cxu-iu' -> 'ĉuiu'. (The
+// checker leaves 'cxiutage' unanalyzed, so x-system text must be converted first.)
+// Capitals are not restored afterwards, so the result is not for display.
+// See check_word_normalized().
+pub fn normalize(word: &str) -> String {
+    return remove_hyphens(&compose_accents(&x_to_accent(word))).to_lowercase();
 }
 
 // Capitalize the first letter of a word: kanado -> Kanado.
@@ -63,8 +340,10 @@ pub fn capitalize(s: &str) -> String {
 }
 
 // Convert an Esperanto word from x-format to Unicode, then print the result.
-// Eg. 'cxirkaux' prints as 'ĉirkaŭ'. 
+// Eg. 'cxirkaux' prints as 'ĉirkaŭ'. (Not in WebAssembly, which has no standard output.)
+#[cfg(not(target_arch = "wasm32"))]
 pub fn print_eo(s: &str) { print!("{}", x_to_accent(s)); }
+#[cfg(not(target_arch = "wasm32"))]
 pub fn println_eo(s: &str) { println!("{}", x_to_accent(s)); }
 
 
@@ -81,6 +360,10 @@ pub fn println_eo(s: &str) { println!("{}", x_to_accent(s)); }
 // Return:
 //      analyzed result with original case restored
 //
+// If the analyzed form is longer than the original (for example, because
+// hyphens were removed from the original), the remaining analyzed characters
+// are kept as they are, rather than causing a panic.
+//
 pub fn restore_capitals(original: &str, analyzed: &str) -> String {
     let original_chars: Vec<_> = original.chars().collect();
     let analyzed_chars = analyzed.chars();
@@ -90,11 +373,124 @@ pub fn restore_capitals(original: &str, analyzed: &str) -> String {
         if ch == '.' {
             result.push(ch);
         }
-        else {
+        else if index < original_chars.len() {
             result.push(original_chars[index]);
             index += 1;
         }
+        else {
+            result.push(ch);
+        }
     }
     return result;
 }
 
+
+/*
+ * words()
+ *
+ * Finds the words of a text, as the spell checker divides them. Hyphens, soft
+ * hyphens and apostrophes are part of words, numbers are separate words, and
+ * periods are kept in abbreviations such as 'k.t.p.'.
+ *
+ * Params:
+ *    text
+ * Return:
+ *    iterator over the byte offset and slice of each word
+ */
+pub fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    return tokenize(text).into_iter()
+                         .filter(|token| token.is_word)
+                         .map(|token| (token.offset, token.text));
+}
+
+
+/*
+ * words_and_gaps()
+ *
+ * Divides a text into words and the gaps between them (spaces and punctuation),
+ * as the spell checker divides them. The tokens are in order, and together they
+ * cover the whole text, so the text can be rebuilt exactly after some words are
+ * changed. Eg. 'ruĝa, bela domo' gives 'ruĝa', ', ', 'bela', ' ', 'domo'.
+ * Words and gaps need not alternate, because a number which follows letters is
+ * a separate word: 'abc3' gives 'abc' and '3'.
+ *
+ * Params:
+ *    text
+ * Return:
+ *    iterator over the tokens (byte offset, slice, and whether it is a word)
+ */
+pub fn words_and_gaps(text: &str) -> impl Iterator<Item = Token<'_>> {
+    return tokenize(text).into_iter();
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn all_caps() {
+        assert_eq!(restore_capitals("FORIGITAJ", "for.ig.it.aj"), "FOR.IG.IT.AJ");
+        let dictionary = load_precompiled().unwrap();
+        let result = check_word("FORIGITAJ", &dictionary);
+        assert!(result.valid);
+        assert_eq!(result.word, "FOR.IG.IT.AJ");
+        let result = check_word("UEA", &dictionary);
+        assert!(result.valid);
+        assert_eq!(result.word, "UEA");
+        for word in ["uea", "Uea", "tejo", "un"].iter() {
+            let result = check_word(word, &dictionary);
+            assert!(!result.valid, "{}", word);
+            assert_eq!(result.reason, Some(Invalidity::Uncapitalized));
+        }
+        assert!(check_word("TEJO", &dictionary).valid);
+        assert!(check_word("ktp", &dictionary).valid);
+    }
+
+    #[test]
+    fn h_system() {
+        let dictionary = load_precompiled().unwrap();
+        let h_to_accent = |word: &str| h_to_accent(word, &dictionary);
+        assert_eq!(h_to_accent("chirkau"), "ĉirkaŭ");
+        assert_eq!(h_to_accent("Shajnas"), "Ŝajnas");
+        assert_eq!(h_to_accent("manghas"), "manĝas");
+        // 'gh' in 'flug.haven.o' is not ĝ.
+        assert_eq!(h_to_accent("flughaveno"), "flughaveno");
+        assert_eq!(h_to_accent("domo"), "domo");
+    }
+
+    #[test]
+    fn orthographies() {
+        assert_eq!(detect_orthography("ĉirkaŭ"), Orthography::Unicode);
+        assert_eq!(detect_orthography("cxirkaux"), Orthography::XSystem);
+        assert_eq!(detect_orthography("CXU"), Orthography::XSystem);
+        assert_eq!(detect_orthography("chirkau"), Orthography::HSystem);
+        assert_eq!(detect_orthography("flughaveno"), Orthography::HSystem);
+        assert_eq!(detect_orthography("domo"), Orthography::Plain);
+        assert_eq!(detect_orthography("aŭto"), Orthography::Unicode);
+    }
+
+    #[test]
+    fn normalized_words() {
+        assert_eq!(normalize("Cxu-iu"), "ĉuiu");
+        assert_eq!(normalize("Kon\u{AD}s\u{302}tru"), "konŝtru");
+        let dictionary = load_precompiled().unwrap();
+        assert!(!check_word("cxiutage", &dictionary).valid);
+        assert!(check_word(&normalize("cxiutage"), &dictionary).valid);
+    }
+
+    #[test]
+    fn combining_accents() {
+        assert_eq!(compose_accents("c\u{302}ielo"), "ĉielo");
+        assert_eq!(compose_accents("C\u{302}IELO"), "ĈIELO");
+        assert_eq!(compose_accents("S\u{302}ajnas"), "Ŝajnas");
+        assert_eq!(compose_accents("au\u{306}to"), "aŭto");
+        assert_eq!(compose_accents("U\u{306}"), "Ŭ");
+        // A circumflex does not belong on u, nor a breve on c.
+        assert_eq!(compose_accents("u\u{302}"), "u\u{302}");
+        assert_eq!(compose_accents("c\u{306}"), "c\u{306}");
+        let dictionary = load_precompiled().unwrap();
+        assert_eq!(check_word("c\u{302}ielo", &dictionary).word, "ĉiel.o");
+    }
+
+}  // tests
@@ -39,24 +39,94 @@ macro_rules! accent_letter {
     })
 }
 
+// has_hat!
+// Test whether an Esperanto letter has a 'hat' (ĉ, ĝ, ŝ, ŭ, ĵ, ĥ).
+#[allow(unused_macros)]
+macro_rules! has_hat {
+    ($ch:expr) => (($ch) == 'ĉ' || ($ch) == 'ĝ' || ($ch) == 'ŝ' ||
+                            ($ch) == 'ŭ' || ($ch) == 'ĥ' || ($ch) == 'ĵ' ||
+                            ($ch) == 'Ĉ' || ($ch) == 'Ĝ' || ($ch) == 'Ŝ' ||
+                            ($ch) == 'Ŭ' || ($ch) == 'Ĥ' || ($ch) == 'Ĵ')
+}
+
+// accent_letter_to_x
+// Take the 'hat' off the given letter, and represent it with an x.
+// This is the reverse of accent_letter!
+#[allow(unused_macros)]
+macro_rules! accent_letter_to_x {
+    ($ch:expr) =>  (match $ch {
+        'ĉ' => "cx",
+        'ĝ' => "gx",
+        'ŝ' => "sx",
+        'ŭ' => "ux",
+        'ĵ' => "jx",
+        'ĥ' => "hx",
+        'Ĉ' => "Cx",
+        'Ĝ' => "Gx",
+        'Ŝ' => "Sx",
+        'Ŭ' => "Ux",
+        'Ĵ' => "Jx",
+        'Ĥ' => "Hx",
+         _  => "?",
+    })
+}
+
+// remove_hat
+// Take the 'hat' off the given letter: ĉ -> c, ŭ -> u, etc.
+// Other characters are returned unchanged.
+#[allow(unused_macros)]
+macro_rules! remove_hat {
+    ($ch:expr) =>  (match $ch {
+        'ĉ' => 'c',
+        'ĝ' => 'g',
+        'ŝ' => 's',
+        'ŭ' => 'u',
+        'ĵ' => 'j',
+        'ĥ' => 'h',
+        'Ĉ' => 'C',
+        'Ĝ' => 'G',
+        'Ŝ' => 'S',
+        'Ŭ' => 'U',
+        'Ĵ' => 'J',
+        'Ĥ' => 'H',
+        other => other,
+    })
+}
+
 // is_word_char
 // This macro returns 'true' for word characters such as 'abc',
 // and 'false' for others, such as punctuation and white space.
 #[allow(unused_macros)]
 macro_rules! is_word_char {
     ($ch:expr) => (
-        ($ch) >= 'a' && ($ch) <= 'z' ||
-        ($ch) >= 'A' && ($ch) <= 'Z' ||
-        ($ch) >= 'À' && ($ch) <= 'ʯ' ||
-        ($ch) == '-' ||
-        ($ch) == '­'
+        matches!($ch,
+            'a'..='z' |
+            'A'..='Z' |
+            'À'..='ʯ' |
+            '\u{300}'..='\u{36F}' |   // combining accents, eg. U+0302
+            '-' |
+            '\u{AD}' |
+            '\u{2010}' |
+            '\u{2011}' |
+            '\'' |
+            '’'
+        )
     )
 }
 
+// is_apostrophe
+// This macro returns 'true' for apostrophes (0x0027 and 0x2019), and 'false' otherwise.
+#[allow(unused_macros)]
+macro_rules! is_apostrophe {
+    ($ch:expr) => (($ch) == '\'' || ($ch) == '’')
+}
+
 // is_hyphen
-// This macro returns 'true' for hyphens (0x002D and 0x00AD), and 'false' otherwise.
+// This macro returns 'true' for hyphens (0x002D, 0x00AD, 0x2010 and 0x2011),
+// and 'false' otherwise. Dashes (0x2012 to 0x2015) are punctuation, not hyphens.
 #[allow(unused_macros)]
 macro_rules! is_hyphen {
-    ($ch:expr) => (($ch) == '-' || ($ch) == '­') 
+    ($ch:expr) => (($ch) == '-' || ($ch) == '\u{AD}' ||
+                   ($ch) == '\u{2010}' || ($ch) == '\u{2011}')
 }
 
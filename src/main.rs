@@ -10,37 +10,278 @@ mod macros;
 use std::env;
 use std::process;
 use std::fs::File;
+use std::io;
+use std::io::BufWriter;
 use std::io::prelude::*;
 use std::collections::HashMap;
-use std::collections::HashSet;
 
-use vortaro::*;  // = dictionary
-use entry::*;  // dictionary entry
-use check_word::*;
+use literumilo::*;
+use json::*;
 
-mod suffix;
-mod entry;
-mod ending;
-mod vortaro;
-mod check_word;
-mod morpheme_list;
-mod scan_morphemes;
+mod json;
 
 const HOW_TO_USE: &str = "\nLiterumilo   Rust version: 1.0\n\n\
     ----- (Esperanto sekvas.)\n\
     This program is a spell checker and morphological analyzer for Esperanto.\n\n\
     To list misspelled words from a file: ./literumilo file.txt\n\
     To divide words from a file into morphemes: ./literumilo -m file.txt\n\
+    To read text from standard input: cat file.txt | ./literumilo -\n\
     To check the spelling of a single word: ./literumilo ĉiutage\n\
+    To output the analysis as JSON: ./literumilo --json file.txt\n\
+    To print statistics after the misspelled words: ./literumilo --stats file.txt\n\
+    To use another dictionary file: ./literumilo --dict vortaro.tsv file.txt\n\
+    To add words from a word list: ./literumilo --add miaj_vortoj.txt file.txt\n\
+    To list misspelled words with their positions: ./literumilo --locations file.txt\n\
+    To analyze with several threads: ./literumilo --jobs 4 file.txt\n\
+    To write the output to a file: ./literumilo -m --output out.txt file.txt\n\
+    To suggest corrections for misspelled words: ./literumilo --suggest file.txt\n\
+    To ignore names (capitalized words within sentences): ./literumilo --ignore-proper file.txt\n\
+    To also list valid words with rare roots (rarity 0 to 4): ./literumilo --max-rarity 2 file.txt\n\
+    To check a dictionary file for errors: ./literumilo --lint-dict vortaro.tsv\n\
+    To tag each morpheme with its role (prefix, root, suffix...): ./literumilo --tagged file.txt\n\
+    To show the unknown part of each misspelled word: ./literumilo --missing-roots file.txt\n\
+    To report names written in lower case (usono): ./literumilo --strict-caps file.txt\n\
+    To print the version number: ./literumilo --version\n\
     Accents can be represented by 'x': ./literumilo cxiutage\n\n\
     -----\n\
     Ĉi tiu programo estas literumilo kaj analizilo de morfemoj por Esperanto.\n\n\
     Por listigi misliterumitajn vortojn de dosiero: ./literumilo file.txt\n\
     Por dividi vortojn de dosiero laŭ morfemoj: ./literumilo -m file.txt\n\
+    Por legi tekston de la norma enigo: cat file.txt | ./literumilo -\n\
     Por kontroli la literumadon de unu vorto: ./literumilo ĉiutage\n\
+    Por eligi la analizon kiel JSON: ./literumilo --json file.txt\n\
+    Por presi statistikon post la misliterumitaj vortoj: ./literumilo --stats file.txt\n\
+    Por uzi alian vortaran dosieron: ./literumilo --dict vortaro.tsv file.txt\n\
+    Por aldoni vortojn de vortlisto: ./literumilo --add miaj_vortoj.txt file.txt\n\
+    Por listigi misliterumitajn vortojn kun iliaj pozicioj: ./literumilo --locations file.txt\n\
+    Por analizi per pluraj fadenoj: ./literumilo --jobs 4 file.txt\n\
+    Por skribi la eligon al dosiero: ./literumilo -m --output out.txt file.txt\n\
+    Por proponi korektojn de misliterumitaj vortoj: ./literumilo --suggest file.txt\n\
+    Por ignori nomojn (majusklajn vortojn ene de frazoj): ./literumilo --ignore-proper file.txt\n\
+    Por ankaŭ listigi validajn vortojn kun raraj radikoj (rareco 0 ĝis 4): ./literumilo --max-rarity 2 file.txt\n\
+    Por kontroli vortaran dosieron pri eraroj: ./literumilo --lint-dict vortaro.tsv\n\
+    Por marki ĉiun morfemon per ĝia rolo (prefikso, radiko, sufikso...): ./literumilo --tagged file.txt\n\
+    Por montri la nekonatan parton de ĉiu misliterumita vorto: ./literumilo --missing-roots file.txt\n\
+    Por raporti nomojn skribitajn minuskle (usono): ./literumilo --strict-caps file.txt\n\
+    Por presi la version: ./literumilo --version\n\
     Oni povas anstataŭigi supersignon per 'x': ./literumilo cxiutage\n\n\
     Klivo <indriko@yahoo.com> 2020";
 
+/*
+ * lint_file()
+ *
+ * Checks a dictionary file, and prints its errors, one per line.
+ *
+ * Params:
+ *    path of the dictionary file
+ * Return:
+ *    exit code: 0 if the file has no errors, 1 otherwise
+ */
+fn lint_file(path: &str) -> i32 {
+    let dictionary_source = match std::fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(reason) => {
+            eprintln!("Could not read dictionary {}: {}", path, reason);
+            return 1;
+        }
+    };
+    let errors = lint_dictionary(&dictionary_source);
+    for error in &errors {
+        println!("{}", error);
+    }
+    if errors.is_empty() { return 0; }
+    eprintln!("{}: {} errors", path, errors.len());
+    return 1;
+}  // lint_file
+
+
+/*
+ * Settings
+ * Command line settings.
+ * Fields:
+ *     'morpheme_mode' - True = morphological analyzer, False = spell checker
+ *     'json' - output the analysis of each word as JSON
+ *     'stats' - print summary statistics to stderr after a spell check
+ *     'locations' - list each misspelled word with its line and column
+ *     'jobs' - the number of threads which analyze the text
+ *     'suggest' - follow each misspelled word with suggested corrections
+ *     'ignore_proper' - do not report capitalized words within a sentence (names)
+ *     'max_rarity' - report valid words whose first morpheme is rarer than this
+ *     'tagged' - in morpheme mode, follow each morpheme with its role
+ *     'missing_roots' - follow each misspelled word with its known and unknown parts
+ *     'options' - settings for check_word_with_options(), eg. strict_caps
+ */
+struct Settings {
+    morpheme_mode: bool,
+    json: bool,
+    stats: bool,
+    locations: bool,
+    jobs: usize,
+    suggest: bool,
+    ignore_proper: bool,
+    max_rarity: Option<usize>,
+    tagged: bool,
+    missing_roots: bool,
+    options: Options,
+}
+
+// The number of corrections which are suggested for a misspelled word.
+const MAX_SUGGESTIONS: usize = 3;
+
+/*
+ * print_stats()
+ *
+ * Prints the word counts of a report to stderr, so that they do not mix with
+ * the list of misspelled words.
+ *
+ * Params:
+ *     report
+ */
+fn print_stats(report: &FileReport) {
+    let ratio = if report.word_count > 0 {
+        report.misspelled_count as f64 / report.word_count as f64
+    } else { 0.0 };
+    eprintln!("Words: {}", report.word_count);
+    eprintln!("Distinct words: {}", report.distinct_count);
+    eprintln!("Misspelled words: {}", report.misspelled_count);
+    eprintln!("Misspelled ratio: {:.2}%", ratio * 100.0);
+}  // print_stats()
+
+
+/*
+ * with_missing_root()
+ *
+ * Follows a misspelled word with the part which can be divided into known
+ * morphemes, and the unknown rest, eg. 'mislernfrobo -> mis.lern + 'frobo''.
+ * (See missing_root().) A word whose morphemes are all known, but do not
+ * combine validly, is shown alone.
+ *
+ * Params:
+ *     misspelled word
+ *     dictionary
+ * Return:
+ *     word with its unknown part
+ */
+fn with_missing_root(word: &str, dictionary: &Dictionary) -> String {
+    match missing_root(word, dictionary) {
+        Some((known, rest)) if known.is_empty() => return format!("{} -> '{}'", word, rest),
+        Some((known, rest)) => return format!("{} -> {} + '{}'", word, known, rest),
+        None => return String::from(word),
+    }
+}  // with_missing_root()
+
+
+/*
+ * with_suggestions()
+ *
+ * Follows a misspelled word with suggested corrections, eg.
+ * 'forgesitaj -> forgesita, forgesitej'. The suggestions for each word are
+ * calculated once, and kept in the cache.
+ *
+ * Params:
+ *     misspelled word
+ *     dictionary
+ *     cache of suggestions
+ * Return:
+ *     word with suggestions, or the word alone if there are none
+ */
+fn with_suggestions(word: &str, dictionary: &Dictionary,
+                    cache: &mut HashMap<String, String>) -> String {
+    if let Some(line) = cache.get(word) {
+        return line.clone();
+    }
+    let suggestions = suggest(word, dictionary, MAX_SUGGESTIONS);
+    let line = if suggestions.is_empty() {
+        String::from(word)
+    } else {
+        format!("{} -> {}", word, suggestions.join(", "))
+    };
+    cache.insert(String::from(word), line.clone());
+    return line;
+}  // with_suggestions()
+
+
+/*
+ * print_analysis()
+ *
+ * Analyze a string of text, and print the report. (See analyze_text_with_options().)
+ * If the mode is False, print a list of the unknown words in the text.
+ * If the mode is True, print the analyzed text with each known word
+ * divided into morphemes (separated by periods).
+ * In JSON mode, print an array with the analysis of each word.
+ * In locations mode, print each misspelled word as 'file.txt:12:5: forgesita'.
+ * In suggest mode, follow each misspelled word with suggested corrections.
+ *
+ * Params:
+ *     text
+ *     name of the source of the text, eg. 'file.txt'
+ *     settings
+ *     dictionary
+ *     output
+ * Return:
+ *     Ok, or an error from writing the output
+ */
+fn print_analysis(text: &str, source: &str, settings: &Settings,
+                  dictionary: &Dictionary, out: &mut dyn Write) -> io::Result<()> {
+
+    let morpheme_mode = settings.morpheme_mode && !settings.json;
+    let mut suggestion_cache: HashMap<String, String> = HashMap::new();
+    if settings.locations && !morpheme_mode && !settings.json {
+        for error in find_errors_with_options(text, dictionary, &settings.options) {
+            if settings.ignore_proper && is_proper_noun(&error.word, &text[..error.offset]) {
+                continue;
+            }
+            let word = if settings.missing_roots {
+                with_missing_root(&error.word, dictionary)
+            } else if settings.suggest {
+                with_suggestions(&error.word, dictionary, &mut suggestion_cache)
+            } else { error.word };
+            writeln!(out, "{}:{}:{}: {}", source, error.line, error.column, word)?;
+        }
+        return Ok(());
+    }
+
+    let options = ReportOptions {
+        check: settings.options.clone(),
+        ignore_proper: settings.ignore_proper,
+        max_rarity: settings.max_rarity,
+        tagged: settings.tagged,
+        results: settings.json,
+        jobs: settings.jobs,
+    };
+    let report = analyze_text_with_options(text, dictionary, morpheme_mode, &options);
+
+    if let Some(analyzed_text) = &report.analyzed_text {
+        write!(out, "{}", analyzed_text)?;
+    }
+    else if settings.json {
+        let objects: Vec<String> = report.results.iter()
+            .map(|(word, result)| result_to_json(word, result)).collect();
+        writeln!(out, "{}", json_array(&objects))?;
+    }
+    else {
+        for word in &report.misspelled {
+            if settings.missing_roots {
+                writeln!(out, "{}", with_missing_root(word, dictionary))?;
+            }
+            else if settings.suggest {
+                writeln!(out, "{}", with_suggestions(word, dictionary, &mut suggestion_cache))?;
+            }
+            else {
+                writeln!(out, "{}", word)?;
+            }
+        }
+        if settings.max_rarity.is_some() {
+            for (word, rarity) in &report.unusual {
+                writeln!(out, "{} (unusual word, rarity {})", word, rarity)?;
+            }
+        }
+        if settings.stats { print_stats(&report); }
+    }
+    return Ok(());
+}  // print_analysis()
+
+
 /*
  * analyze_file()
  *
@@ -51,12 +292,14 @@ const HOW_TO_USE: &str = "\nLiterumilo   Rust version: 1.0\n\n\
  *
  * Params:
  *     file name
- *     morpheme mode - True = morphological analyzer, False = spell checker
+ *     settings
  *     dictionary
+ *     output
  * Return:
- *     analyzed text, or list of misspelled words  (str)
+ *     Ok, or an error from writing the output
  */
-fn analyze_file(filename: &str, morpheme_mode: bool, dictionary: HashMap<String, Entry>) {
+fn analyze_file(filename: &str, settings: &Settings, dictionary: &Dictionary,
+                out: &mut dyn Write) -> io::Result<()> {
 
     let path = std::path::Path::new(filename);
     let exists = path.exists();
@@ -67,61 +310,43 @@ fn analyze_file(filename: &str, morpheme_mode: bool, dictionary: HashMap<String,
 
         let path_display = path.display();
         // Open file for reading.
-        let mut file = match File::open(&path) {
+        let mut file = match File::open(path) {
             Err(reason) => panic!("Could not open {}: {}", path_display, reason),
             Ok(file) => file,
         };
 
         // Read the file into a string.
         let mut s = String::new();
-        match file.read_to_string(&mut s) {
-            Err(reason) => panic!("Could not read {}: {}", path_display, reason),
-            Ok(_) => (),
+        if let Err(reason) = file.read_to_string(&mut s) {
+            panic!("Could not read {}: {}", path_display, reason);
         }
 
-        let mut bad_words = HashSet::new();
-
-        let char_iter = s.chars();
-        let mut in_word = false;
-        let mut collected_chars = format!("");
-
-        for ch in char_iter {
-            if is_word_char!(ch) {
-                in_word = true;
-                collected_chars.push(ch);
-            }
-            else {
-                if in_word {
-                    let result = check_word(&collected_chars, &dictionary);
-                    if morpheme_mode {
-                        print!("{}", result.word);
-                    }
-                    else {
-                        if !result.valid { bad_words.insert(format!("{}", &collected_chars)); }
-                    }
-                    collected_chars.clear();
-                }
-                in_word = false;
-                if morpheme_mode { print!("{}", ch); }
-            }
-        }
+        return print_analysis(&s, filename, settings, dictionary, out);
+    }
+    return Ok(());
+}  // analyze_file()
 
-        if in_word {
-            let result = check_word(&collected_chars, &dictionary);
-            if morpheme_mode {
-                print!("{}", result.word);
-            }
-            else {
-                if !result.valid { bad_words.insert(format!("{}", &collected_chars)); }
-            }
-            collected_chars.clear();
-        }
 
-        if !morpheme_mode {
-            for word in bad_words { println!("{}", word); }
-        }
+/*
+ * analyze_stdin()
+ *
+ * Read all of standard input, and analyze it as analyze_file() does.
+ *
+ * Params:
+ *     settings
+ *     dictionary
+ *     output
+ * Return:
+ *     Ok, or an error from writing the output
+ */
+fn analyze_stdin(settings: &Settings, dictionary: &Dictionary,
+                 out: &mut dyn Write) -> io::Result<()> {
+    let mut s = String::new();
+    if let Err(reason) = io::stdin().read_to_string(&mut s) {
+        panic!("Could not read standard input: {}", reason);
     }
-}  // analyze_file()
+    return print_analysis(&s, "<stdin>", settings, dictionary, out);
+}  // analyze_stdin()
 
 
 /*
@@ -131,13 +356,36 @@ fn analyze_file(filename: &str, morpheme_mode: bool, dictionary: HashMap<String,
  * parameter is a file name. If it is a file name, main calls analyze_file().
  *
  * If no file with the given name exists, the function calls check_word(),
- * assuming that the given parameter is an individual word.
+ * assuming that the given parameter is an individual word. A valid word is
+ * always printed divided into morphemes, eg. 'ĉirkaŭ.ir.is ✓', with or
+ * without -m.
+ *
+ * If the parameter is '-' or '--stdin', the text is read from standard input.
  *
  * If the command line parameter is a file name, main() checks for
  * the 'morpheme_mode' flag (-m), and passes this parameter to
  * analyze_file(). When the morpheme_mode flag is set, analyze_file()
  * will output the entire text with Esperanto words divided by morpheme.
  * Otherwise it will output a list of misspelled words.
+ *
+ * The '--json' flag outputs the analysis of each word as a JSON array.
+ * The '--stats' flag prints word counts (to stderr) after the list of misspelled words.
+ * The '--dict <path>' option loads the dictionary from a file, instead of the embedded one.
+ * The '--add <path>' option adds the words of a supplementary word list to the dictionary.
+ * The '--locations' flag lists each misspelled word with its line and column.
+ * The '--jobs <n>' option analyzes the text with n threads.
+ * The '--output <path>' option writes the output to a file, instead of standard output.
+ * The '--suggest' option follows each misspelled word with suggested corrections.
+ * The '--ignore-proper' option does not report capitalized words within sentences.
+ * The '--max-rarity <n>' option also lists valid words whose first morpheme is rarer than n.
+ * The '--lint-dict <path>' option checks a dictionary file for errors, and exits.
+ * The '--missing-roots' option follows each misspelled word with the part which could be
+ * divided into known morphemes, and the unknown rest, eg. 'mislernfrobo -> mis.lern + 'frobo''.
+ * The '--tagged' option divides words into morphemes, as -m does, and follows each
+ * morpheme with its role, eg. 'for⟨PREF⟩.ig⟨SUF⟩.it⟨PART⟩.aj⟨ENDING:Adjective⟩'.
+ * The '--strict-caps' option reports names which are written in lower case, eg. 'usono'.
+ * The '--version' flag prints the version of the package (eg. 0.1.0), and exits.
+ * The '--help' flag prints the instructions (HOW_TO_USE), as does running without arguments.
  */
 fn main() {
 
@@ -152,43 +400,233 @@ fn main() {
     // 'Morpheme' mode means that the program will divide words in the input file
     // by morphemes. Eg. 'submara' becomes 'sub.mar.a'. Otherwise, the program
     // will output a list of presumably misspelled words.
-    let mut morpheme_mode = false;
-    let mut first_arg = "";
-    let mut second_arg = "";
-
-    if num_args > 0 { first_arg = &args[1]; }
-    if num_args > 1 { second_arg = &args[2]; }
-
-    let mut file_or_word = first_arg;
-    if num_args >= 2 {
-        if first_arg == "-m" {
-            morpheme_mode = true;
-            file_or_word = second_arg;
+    let mut settings = Settings {
+        morpheme_mode: false, json: false, stats: false, locations: false, jobs: 1,
+        suggest: false, ignore_proper: false, max_rarity: None, tagged: false,
+        missing_roots: false, options: Options::default(),
+    };
+    let mut file_or_word = "";
+    let mut dictionary_path: Option<&str> = None;
+    let mut word_list_paths: Vec<&str> = vec![];
+    let mut output_path: Option<&str> = None;
+    let mut lint_path: Option<&str> = None;
+
+    let mut arg_iter = args[1..].iter();
+    while let Some(arg) = arg_iter.next() {
+        match arg.as_str() {
+            "-m" => settings.morpheme_mode = true,
+            "--version" => {
+                println!("{}", env!("CARGO_PKG_VERSION"));
+                process::exit(0);
+            },
+            "--help" => {
+                println!("{}", HOW_TO_USE);
+                process::exit(0);
+            },
+            "--dict" => {
+                match arg_iter.next() {
+                    Some(path) => dictionary_path = Some(path),
+                    None => {
+                        eprintln!("The --dict option requires a file name.");
+                        process::exit(1);
+                    }
+                }
+            },
+            "--output" => {
+                match arg_iter.next() {
+                    Some(path) => output_path = Some(path),
+                    None => {
+                        eprintln!("The --output option requires a file name.");
+                        process::exit(1);
+                    }
+                }
+            },
+            "--lint-dict" => {
+                match arg_iter.next() {
+                    Some(path) => lint_path = Some(path),
+                    None => {
+                        eprintln!("The --lint-dict option requires a file name.");
+                        process::exit(1);
+                    }
+                }
+            },
+            "--add" => {
+                match arg_iter.next() {
+                    Some(path) => word_list_paths.push(path),
+                    None => {
+                        eprintln!("The --add option requires a file name.");
+                        process::exit(1);
+                    }
+                }
+            },
+            "--json" => settings.json = true,
+            "--missing-roots" => settings.missing_roots = true,
+            "--strict-caps" => settings.options.strict_caps = true,
+            "--tagged" => {
+                settings.morpheme_mode = true;
+                settings.tagged = true;
+            },
+            "--stats" => settings.stats = true,
+            "--locations" => settings.locations = true,
+            "--suggest" => settings.suggest = true,
+            "--ignore-proper" => settings.ignore_proper = true,
+            "--max-rarity" => {
+                match arg_iter.next().and_then(|n| n.parse::<usize>().ok()) {
+                    Some(rarity) => settings.max_rarity = Some(rarity),
+                    None => {
+                        eprintln!("The --max-rarity option requires a number from 0 to 4.");
+                        process::exit(1);
+                    }
+                }
+            },
+            "--jobs" => {
+                match arg_iter.next().and_then(|n| n.parse::<usize>().ok()) {
+                    Some(jobs) if jobs > 0 => settings.jobs = jobs,
+                    _ => {
+                        eprintln!("The --jobs option requires a number of threads.");
+                        process::exit(1);
+                    }
+                }
+            },
+            _ => file_or_word = arg,
         }
     }
 
-    // Get the Esperanto dictionary.
-    let dictionary_source = include_str!("vortaro.tsv");
-    let vortaro: HashMap<String, Entry> = make_dictionary(dictionary_source);
+    if let Some(path) = lint_path {
+        process::exit(lint_file(path));
+    }
+
+    if file_or_word.is_empty() {
+        println!("{}", HOW_TO_USE);
+        process::exit(0);
+    }
+
+    // Get the Esperanto dictionary. A dictionary file can replace the embedded one.
+    let dictionary_result = match dictionary_path {
+        Some(dict_path) => {
+            match std::fs::read_to_string(dict_path) {
+                Ok(dictionary_source) => make_dictionary(&dictionary_source),
+                Err(reason) => {
+                    eprintln!("Could not read dictionary {}: {}", dict_path, reason);
+                    process::exit(1);
+                }
+            }
+        },
+        None => load_precompiled(),
+    };
+    let mut vortaro: Dictionary = match dictionary_result {
+        Ok(dictionary) => dictionary,
+        Err(error) => {
+            eprintln!("Invalid dictionary data, {}", error);
+            process::exit(1);
+        }
+    };
+
+    // Add the user's own words.
+    for list_path in word_list_paths {
+        match std::fs::read_to_string(list_path) {
+            Ok(word_list) => {
+                if let Err(error) = add_words(&mut vortaro, &word_list) {
+                    eprintln!("Invalid word list {}, {}", list_path, error);
+                    process::exit(1);
+                }
+            },
+            Err(reason) => {
+                eprintln!("Could not read word list {}: {}", list_path, reason);
+                process::exit(1);
+            }
+        }
+    }
 
     let path = std::path::Path::new(file_or_word);
     let exists = path.exists();
 
-    if exists {   // If there is a file...
-        analyze_file(file_or_word, morpheme_mode, vortaro)
+    // The output goes to standard output, or to a file. It is buffered, and flushed once at the end.
+    let mut out: Box<dyn Write> = match output_path {
+        Some(out_path) => {
+            match File::create(out_path) {
+                Ok(file) => Box::new(BufWriter::new(file)),
+                Err(reason) => {
+                    eprintln!("Could not create {}: {}", out_path, reason);
+                    process::exit(1);
+                }
+            }
+        },
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    let written = if file_or_word == "-" || file_or_word == "--stdin" {   // Read from a pipe.
+        analyze_stdin(&settings, &vortaro, &mut out)
+    }
+    else if exists {   // If there is a file...
+        analyze_file(file_or_word, &settings, &vortaro, &mut out)
     }
     else {  // Must be a word.
         let word = literumilo::x_to_accent(file_or_word);
-        let result = check_word(&word, &vortaro);
-        if result.valid {
-            println!("{} ✓", result.word);
+        let result = check_word_with_options(&word, &vortaro, &settings.options);
+        if settings.json {
+            writeln!(out, "{}", json_array(&[result_to_json(&word, &result)]))
+        }
+        else if result.valid && settings.tagged {
+            writeln!(out, "{} ✓", tagged_form(&result))
+        }
+        else if result.valid {
+            writeln!(out, "{} ✓", result.word)
+        }
+        else if settings.missing_roots {
+            writeln!(out, "✘{}", with_missing_root(&word, &vortaro))
+        }
+        else if settings.suggest {
+            let mut cache = HashMap::new();
+            writeln!(out, "✘{}", with_suggestions(&word, &vortaro, &mut cache))
         }
         else {
-            println!("✘{}", file_or_word);
+            writeln!(out, "✘{}", word)
         }
+    };
+
+    if let Err(reason) = written.and_then(|_| out.flush()) {
+        eprintln!("Could not write the output: {}", reason);
+        process::exit(1);
     }
 }
 
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    // A name at the start of a chunk is not at the start of a sentence.
+    #[test]
+    fn ignore_proper_with_jobs() {
+        let text = "la hundo kaj\nZorbak kuris\n".repeat(200);
+        let dictionary = load_precompiled().unwrap();
+        for jobs in [1, 2, 4, 8].iter() {
+            let settings = Settings {
+                morpheme_mode: false, json: false, stats: false, locations: false,
+                jobs: *jobs, suggest: false, ignore_proper: true, max_rarity: None,
+                tagged: false, missing_roots: false, options: Options::default(),
+            };
+            let mut out: Vec<u8> = vec![];
+            print_analysis(&text, "test", &settings, &dictionary, &mut out).unwrap();
+            assert_eq!(String::from_utf8(out).unwrap(), "", "--jobs {}", jobs);
+        }
+    }
+
+    #[test]
+    fn json_reason() {
+        let dictionary = load_precompiled().unwrap();
+        let json = result_to_json("malrea", &check_word("malrea", &dictionary));
+        assert!(json.contains("\"reason\": { \"kind\": \"BadSynthesis\", \"index\": 1 }"));
+        let json = result_to_json("xyzq", &check_word("xyzq", &dictionary));
+        assert!(json.contains("\"reason\": { \"kind\": \"NoEnding\" }"));
+        let json = result_to_json("domo", &check_word("domo", &dictionary));
+        assert!(json.contains("\"reason\": null"));
+    }
+
+}  // tests
+
+
 // Memory made safe,
 // Generations turn to Rust,
 // Silicon will sing.
@@ -1,44 +1,77 @@
 // Define a Morpheme list. This list is used to analyse the synthesis of compound words.
 //
-// Why is morpheme_list a vector of dictionary entries, rather than a vector
+// Why is morpheme_list a vector of copy-on-write entries, rather than a vector
 // of references to dictionary entries? The algorithm uses this list to analyze
 // word synthesis. Sometimes, it needs to modify an entry in the morpheme list.
+// Most entries are only read, so an entry is cloned only when it is modified.
 //
 // Klivo 2020-05-08
 
+use std::borrow::Cow;
+
 use super::entry::*;
 use super::ending::*;
+use super::check_word::Invalidity;
+
+pub const MAX_MORPHEMES: usize = 9; // The default maximum number of morphemes in a compound word.
 
-const  MAX_MORPHEMES: usize = 9; // The maximum number of morphemes in a compound word.
+// The maximum number of times find_morpheme() may be called for one word. The number
+// of morphemes limits the depth of the search, but a long word made of many short
+// morphemes could still take long to reject. Real words need far fewer steps.
+pub const MAX_STEPS: usize = 10_000;
+
+// After this many steps, find_morpheme() abandons divisions which cannot succeed.
+// (See min_morphemes() in check_word.rs.) Real words need fewer than 50 steps.
+pub const PRUNE_AFTER_STEPS: usize = 100;
 
 #[derive(Debug)]
 pub struct Morphemes<'a> {
     last_index: usize,   // last index written to
-    morpheme_list: Vec<Entry>,
+    morpheme_list: Vec<Cow<'a, Entry>>,
     ending: Ending<'a>,
+    failure: Option<Invalidity>,   // why the word could not be analyzed
+    steps: usize,   // number of calls to find_morpheme()
+    word: String,   // the word being divided, without its ending
+    min_morphemes: Vec<Option<usize>>,   // see set_min_morphemes()
+    divisions: Option<Vec<Vec<Cow<'a, Entry>>>>,   // all valid divisions, if they are collected
+    fewest: Option<Vec<Cow<'a, Entry>>>,   // division with the fewest morphemes (see seek_fewer())
+    seeking_fewer: bool,   // true if the search continues after the first valid division
+    tracking: bool,   // true if the furthest progress is recorded (see track_progress())
+    progress: usize,   // bytes of the word covered by the furthest division
+    progress_division: Vec<Cow<'a, Entry>>,   // morphemes of the furthest division
 }
 
-impl Morphemes<'_> {
+impl<'a> Morphemes<'a> {
 
     /*
      * new - Factory for a new morpheme list.
      *
      * Params:
      *    ending (grammatical ending of word)
+     *    maximum number of morphemes
      * Returns:
      *    morpheme list (Morphemes)
      */
-    pub fn new(ending: Ending) -> Morphemes {
+    pub fn new(ending: Ending<'a>, max_morphemes: usize) -> Morphemes<'a> {
 
-        let entry = Entry::empty();
-        let mut morpheme_list: Vec<Entry>  = Vec::with_capacity(MAX_MORPHEMES);
+        let mut morpheme_list: Vec<Cow<'a, Entry>>  = Vec::with_capacity(max_morphemes);
 
-        for _ in 0..MAX_MORPHEMES { morpheme_list.push(entry.clone()); }
+        for _ in 0..max_morphemes { morpheme_list.push(Cow::Owned(Entry::empty())); }
 
         Morphemes {
             last_index: 0,
-            morpheme_list: morpheme_list,
-            ending: ending,
+            morpheme_list,
+            ending,
+            failure: None,
+            steps: 0,
+            word: String::new(),
+            min_morphemes: vec![],
+            divisions: None,
+            fewest: None,
+            seeking_fewer: false,
+            tracking: false,
+            progress: 0,
+            progress_division: vec![],
         }
     }  // end of new()
 
@@ -52,51 +85,264 @@ impl Morphemes<'_> {
      *     optional entry (morpheme)
      */
     pub fn get(&self, index: usize) -> Option<&Entry> {
-        self.morpheme_list.get(index)
+        self.morpheme_list.get(index).map(|entry| entry.as_ref())
     }
 
     /*
      * get_mut - get one entry (mutable borrow)
      *
+     * An entry borrowed from the dictionary is cloned here, before it is modified.
+     *
      * Params:
      *     index of entry
      * Return:
      *     optional entry (morpheme)
      */
     pub fn get_mut(&mut self, index: usize) -> Option<&mut Entry> {
-        self.morpheme_list.get_mut(index)
+        self.morpheme_list.get_mut(index).map(|entry| entry.to_mut())
     }
 
     /*
      * put - put one entry
      *
-     * Make a clone of the given entry, because we may
-     * need to modify it later.
+     * The entry is borrowed. It will be cloned only if it must be modified.
      *
      * Params:
      *     index of entry
      *     entry to insert into list
      */
-    pub fn put(&mut self, index: usize, entry: &Entry) {
+    pub fn put(&mut self, index: usize, entry: &'a Entry) {
+        self.last_index = index;
+        self.morpheme_list[index] = Cow::Borrowed(entry);
+    }
+
+    /*
+     * put_owned - put one entry which is not in the dictionary
+     *
+     * Params:
+     *     index of entry
+     *     entry to insert into list, eg. a separator
+     */
+    pub fn put_owned(&mut self, index: usize, entry: Entry) {
         self.last_index = index;
-        self.morpheme_list[index] = entry.clone();
+        self.morpheme_list[index] = Cow::Owned(entry);
     }
 
     // Getter for last_index.
     pub fn last_index(&self) -> usize { self.last_index }
 
+    // Iterates over the morphemes which have been found, from index 0 to last_index.
+    // Outside the crate, the morphemes of a word are read from AnalysisResult::iter().
+    pub fn iter(&self) -> impl Iterator<Item = &Entry> {
+        return self.morpheme_list[..=self.last_index].iter().map(|entry| entry.as_ref());
+    }
+
     // Getter for type (part of speech) of ending. Eg. Substantive, Verb...
     pub fn type_of_ending(&self) -> POS { self.ending.pos }
 
+    // Getter for the grammatical ending.
+    pub fn ending(&self) -> &Ending<'_> { &self.ending }
+
+    /*
+     * fail - Records the reason why an analysis failed.
+     *
+     * While dividing a word, the algorithm backtracks through many failed
+     * divisions. The most informative reason is kept: a synthesis error is
+     * preferred to too many morphemes, and a later synthesis error is
+     * preferred to an earlier one.
+     *
+     * Params:
+     *     reason for failure
+     * Return:
+     *     false (for convenience)
+     */
+    pub fn fail(&mut self, reason: Invalidity) -> bool {
+        let replace = match (self.failure, reason) {
+            (None, _) => true,
+            (Some(Invalidity::BadSynthesis { index: old }),
+             Invalidity::BadSynthesis { index: new }) => new > old,
+            (Some(Invalidity::BadSynthesis { .. }), _) => false,
+            (_, Invalidity::BadSynthesis { .. }) => true,
+            (Some(_), _) => false,
+        };
+        if replace { self.failure = Some(reason); }
+        return false;
+    }
+
+    // Getter for the reason of failure. If nothing was recorded, no root was found.
+    pub fn failure(&self) -> Invalidity {
+        self.failure.unwrap_or(Invalidity::RootNotFound)
+    }
+
+    /*
+     * step - Counts one step of the search for morphemes.
+     *
+     * Return:
+     *     false if the search has taken too many steps, and must stop
+     */
+    pub fn step(&mut self) -> bool {
+        self.steps += 1;
+        return self.steps <= MAX_STEPS;
+    }
+
+    pub fn steps(&self) -> usize { self.steps }
+
+    // Setter and getter for the word being divided.
+    pub fn set_word(&mut self, word: &str) { self.word = String::from(word); }
+    pub fn word(&self) -> &str { &self.word }
+
+    // True if the minimum numbers of morphemes have been calculated.
+    pub fn has_min_morphemes(&self) -> bool { !self.min_morphemes.is_empty() }
+
+    /*
+     * set_min_morphemes - Sets the minimum number of morphemes into which each
+     * rest of the word can be divided. (See min_morphemes() in check_word.rs.)
+     *
+     * Params:
+     *     minimum counts, indexed by the length in bytes of the rest of the word
+     */
+    pub fn set_min_morphemes(&mut self, min_morphemes: Vec<Option<usize>>) {
+        self.min_morphemes = min_morphemes;
+    }
+
+    /*
+     * min_morphemes - Gets the minimum number of morphemes into which the
+     * rest of the word can be divided.
+     *
+     * Params:
+     *     length in bytes of the rest of the word
+     * Return:
+     *     minimum count, or None if the rest cannot be divided into morphemes
+     */
+    pub fn min_morphemes(&self, rest_length: usize) -> Option<usize> {
+        return self.min_morphemes.get(rest_length).copied().flatten();
+    }
+
+    /*
+     * collect_divisions - Makes the search collect every valid division of the
+     * word, instead of stopping at the first. (See analyze_all().)
+     */
+    pub fn collect_divisions(&mut self) { self.divisions = Some(vec![]); }
+
+    // True if every valid division is being collected.
+    pub fn collecting(&self) -> bool { self.divisions.is_some() }
+
+    /*
+     * record_division - Records the current division of the word.
+     */
+    pub fn record_division(&mut self) {
+        let division = self.morpheme_list[..=self.last_index].to_vec();
+        if let Some(divisions) = &mut self.divisions { divisions.push(division); }
+    }
+
+    /*
+     * division_forms - Gets the display forms of the collected divisions.
+     *
+     * Return:
+     *     list of divisions, eg. 'sen.depend.a', without duplicates
+     */
+    pub fn division_forms(&self) -> Vec<String> {
+        let mut forms: Vec<String> = vec![];
+        for division in self.divisions.iter().flatten() {
+            let form = join_morphemes(division, self.ending.ending);
+            if !forms.contains(&form) { forms.push(form); }
+        }
+        return forms;
+    }
+
+    /*
+     * track_progress - Makes the search remember the division which gets furthest
+     * into the word, so that the unknown part of an invalid word can be shown.
+     * (See missing_root().)
+     */
+    pub fn track_progress(&mut self) { self.tracking = true; }
+
+    /*
+     * record_progress - Records the morphemes up to 'index', if they cover more of
+     * the word than any division so far.
+     *
+     * Params:
+     *     index of the last morpheme
+     *     bytes of the word which the morphemes cover
+     */
+    pub fn record_progress(&mut self, index: usize, covered: usize) {
+        if !self.tracking || covered <= self.progress { return; }
+        self.progress = covered;
+        self.progress_division = self.morpheme_list[..=index].to_vec();
+    }
+
+    /*
+     * progress - Gets the furthest division of the word. (See record_progress().)
+     *
+     * Return:
+     *     bytes covered, and the morphemes joined by periods, eg. 'ne.konat'
+     */
+    pub fn progress(&self) -> (usize, String) {
+        let words: Vec<&str> = self.progress_division.iter().map(|entry| entry.word.as_str()).collect();
+        return (self.progress, words.join("."));
+    }
+
+    /*
+     * seek_fewer - Makes the search continue after the first valid division,
+     * which has the longest first morphemes, to look for a division with fewer
+     * morphemes. Eg. 'send.e.pend.a' is found first, but 'sen.depend.a' is better.
+     * (See record_fewer() and use_fewest().)
+     */
+    pub fn seek_fewer(&mut self) { self.seeking_fewer = true; }
+
+    // True if the search continues after the first valid division.
+    pub fn seeking_fewer(&self) -> bool { self.seeking_fewer }
+
+    // The number of morphemes in the best division so far, if one has been found.
+    pub fn fewest(&self) -> Option<usize> {
+        return self.fewest.as_ref().map(|division| division.len());
+    }
+
+    /*
+     * record_fewer - Records the current division of the word, if it has fewer
+     * morphemes than the best division so far.
+     *
+     * Return:
+     *     true if no division can have fewer morphemes, so the search can stop
+     */
+    pub fn record_fewer(&mut self) -> bool {
+        let count = self.last_index + 1;
+        if self.fewest().is_none_or(|fewest| count < fewest) {
+            self.fewest = Some(self.morpheme_list[..count].to_vec());
+        }
+        // The first morpheme never covers the whole word, so a division which
+        // is searched for has at least two morphemes.
+        return self.fewest().is_some_and(|fewest| fewest <= 2);
+    }
+
+    /*
+     * use_fewest - Puts the division with the fewest morphemes back into the
+     * morpheme list. (See seek_fewer().)
+     *
+     * Return:
+     *     true if a valid division was found
+     */
+    pub fn use_fewest(&mut self) -> bool {
+        if let Some(division) = self.fewest.take() {
+            self.last_index = division.len() - 1;
+            for (index, entry) in division.into_iter().enumerate() {
+                self.morpheme_list[index] = entry;
+            }
+            return true;
+        }
+        return false;
+    }
+
     /*
      * print - Prints collected entries from the morpheme list.
      *
      * Parameters:
      *     borrowed self
      */
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn _print(&self) {
-        for i in 0..=self.last_index {
-            println!("-- {:?}", self.morpheme_list.get(i));
+        for entry in self.iter() {
+            println!("-- {:?}", entry);
         }
     }
 
@@ -113,15 +359,7 @@ impl Morphemes<'_> {
      *     string of morphemes
      */
     pub fn display_form(&self) -> String {
-        let mut s = String::from("");
-        if let Some(m) = self.morpheme_list.get(0) { s = format!("{}", m.word); }
-        for i in 1..=self.last_index {
-            if let Some(m) = self.morpheme_list.get(i) {
-                s = format!("{}.{}", s, m.word);
-            }
-        }
-        let s2 = format!("{}.{}", s, self.ending.ending);
-        return s2;
+        return join_morphemes(&self.morpheme_list[..=self.last_index], self.ending.ending);
     }
 
     /*
@@ -130,7 +368,7 @@ impl Morphemes<'_> {
      * This method scans the collected morphemes in morpheme_list
      * to determine how many separators vowels there are. For example,
      * 'last.A.temp.e' has 1 separator vowel (A). (last.temp.e is a little
-     * harder to pronounce.) This program will only allow one per word.
+     * harder to pronounce.) By default, only one is allowed per word.
      *
      * Parameter:
      *     borrowed self
@@ -138,13 +376,29 @@ impl Morphemes<'_> {
      *     count of separators
      */
     pub fn count_separators(&self) -> u32 {
-        let mut count = 0;
-        for index in 0 ..= self.last_index {
-            if self.morpheme_list[index].flag == Flag::Separator {
-                count += 1;
-            }
-        }
-        return count;
+        return self.iter().filter(|entry| entry.flag == Flag::Separator).count() as u32;
     }
 
 }  // end of impl Morphemes
+
+
+/*
+ * join_morphemes
+ *
+ * Joins morphemes and a grammatical ending with periods, eg. 'for.ig.it.a'.
+ *
+ * Params:
+ *     morphemes
+ *     grammatical ending
+ * Return:
+ *     string of morphemes
+ */
+fn join_morphemes(morphemes: &[Cow<'_, Entry>], ending: &str) -> String {
+    let mut s = String::new();
+    for m in morphemes {
+        s.push_str(&m.word);
+        s.push('.');
+    }
+    s.push_str(ending);
+    return s;
+}
@@ -19,6 +19,7 @@
 // Klivo 2020-05-08
 
 use super::entry::*;
+use super::check_word::*;
 use super::morpheme_list::*;
 
 /*
@@ -92,6 +93,32 @@ fn check_cxi(index: usize, morpheme_list: &Morphemes) -> bool {
 
 
 
+/*
+ * check_duon()
+ *
+ * Check prefix duon-, meaning 'half' or, for relatives, 'step-'.
+ * Eg. 'duon-hor-o' (half hour), 'duon-frat-o' (half brother).
+ * Valid before substantives and relatives. (Duono, 'a half', is du-on-o.)
+ *
+ * For a description of parameters see check_acx().
+ */
+fn check_duon(index: usize, morpheme_list: &Morphemes) -> bool {
+
+    if index != 0 { return false; }
+    let difference = morpheme_list.last_index() - index;
+
+    if difference > 0 {  // Make sure at least one morpheme follows.
+        if let Some(next_entry) = &morpheme_list.get(index + 1) {
+            let pos = next_entry.part_of_speech;
+            if pos == POS::Substantive || pos == POS::SubstantiveVerb { return true; }
+            if next_entry.meaning == Meaning::Parenco { return true; }
+        }
+    }
+    return false;
+
+}  // check_duon
+
+
 /*
  * check_eks()
  *
@@ -144,6 +171,28 @@ fn check_ge(index: usize, morpheme_list: &Morphemes) -> bool {
 
 
 
+/*
+ * check_fi
+ *
+ * Check prefix fi-, meaning 'shameful, disgusting'.
+ * Eg. 'fi-hom-o' (scoundrel), 'fi-ag-o' (vile act).
+ * Valid at the front of a word, before a substantive, adjective or verb root.
+ * (Fi can also stand alone as an interjection. That is handled by the dictionary.)
+ *
+ * For a description of parameters see check_acx().
+ */
+fn check_fi(index: usize, morpheme_list: &mut Morphemes) -> bool {
+
+    if index != 0 { return false; }
+    if let Some(entry) = &morpheme_list.get(index + 1) {
+        return matches!(entry.part_of_speech, POS::Substantive | POS::Adjective |
+                                              POS::Verb | POS::SubstantiveVerb);
+    }
+    return false;
+
+}  // check_fi
+
+
 /*
  * check_kun
  *
@@ -345,14 +394,7 @@ fn check_sen(index: usize, morpheme_list: &mut Morphemes) -> bool {
     let last = morpheme_list.last_index();
 
     if let Some(last_entry) = &morpheme_list.get(last) {
-        let w = &last_entry.word[..];
-        let valid = match w {
-            "ul" => true,
-            "aĵ" => true,
-            "ej" => true,
-            _ => false,
-        };
-        return valid;
+        return matches!(&last_entry.word[..], "ul" | "aĵ" | "ej");
     }
 
     return false;
@@ -505,11 +547,13 @@ fn check_prefix(_prefix: &str, index: usize, morpheme_list: &mut Morphemes) -> b
         }
     }
 
+    // ĉef, mem and vic are unlimited roots in vortaro.tsv (ĉef.redakt.ant.o, mem.cert.a, vic.ord.o), not prefixes.
     match _prefix {
         "al" => return check_prepositional_prefix(index, morpheme_list),
         "anstataŭ" => return check_first(index, morpheme_list),
         "antaŭ" => return check_first(index, morpheme_list),
         "apud" => return check_prepositional_prefix(index, morpheme_list),
+        "bis" => return check_adverbial_prefix(index, morpheme_list),
         "bo" => return check_bo(index, morpheme_list),
         "cis" => return check_cis(index, morpheme_list),
         "ĉe" => return check_prepositional_prefix(index, morpheme_list),
@@ -518,11 +562,13 @@ fn check_prefix(_prefix: &str, index: usize, morpheme_list: &mut Morphemes) -> b
         "de" => return check_prepositional_prefix(index, morpheme_list),
         "dis" => return check_adverbial_prefix(index, morpheme_list),
         "dum" => return check_prepositional_prefix(index, morpheme_list),
+        "duon" => return check_duon(index, morpheme_list),
         "ek" => return check_adverbial_prefix(index, morpheme_list),
         "eks" => return check_eks(index, morpheme_list),
         "ekster" => return check_first(index, morpheme_list),
         "el" => return check_prepositional_prefix(index, morpheme_list),
         "en" => return check_prepositional_prefix(index, morpheme_list),
+        "fi" => return check_fi(index, morpheme_list),
         "for" => return check_adverbial_prefix(index, morpheme_list),
         "ge" => return check_ge(index, morpheme_list),
         "ĝis" => return check_prepositional_prefix(index, morpheme_list),
@@ -567,7 +613,7 @@ fn check_prefix(_prefix: &str, index: usize, morpheme_list: &mut Morphemes) -> b
  *
  * A limited verb-morpheme may combine only with a suffix and/or a prefix.
  * A limited animal-morpheme may combine only with vir-, -in, -id ktp.
- * A limited relationship-morpheme (father, brother) may combine only with pra-, ge- or -in.
+ * A limited relationship-morpheme (father, brother) may combine only with bo-, duon-, ge-, pra- or -in.
  * A limited ethnicity-morpheme may combine with -in, -land, etc.
  *
  * Params:
@@ -611,6 +657,7 @@ fn check_limited_synthesis(_morpheme: &str, index: usize, morpheme_list: &mut Mo
                 let m = &prev.word[..];
                 match m {
                     "bo" => (),
+                    "duon" => (),
                     "ge" => (),
                     "pra" => (),
                     _ => return false,
@@ -654,6 +701,7 @@ fn check_limited_synthesis(_morpheme: &str, index: usize, morpheme_list: &mut Mo
             if let Some(next) = &morpheme_list.get(index + 1) {
                 let m = &next.word[..];
                 match m {
+                    "i" => (),
                     "in" => (),
                     "id" => (),
                     "land" => (),
@@ -704,6 +752,31 @@ fn valid_separator(pos: POS, index: usize, morpheme_list: &mut Morphemes) -> boo
 }  // valid_separator
 
 
+/*
+ * inherit_transitivity()
+ *
+ * A participle has the transitivity of the verb which it follows. Eg. the 'it' of
+ * 'frap.it.aĵ.o' is transitive, like 'frap'. The dictionary entries of the participles
+ * are intransitive, so the transitivity is copied from the previous morpheme, before
+ * the suffixes which follow the participle are checked.
+ *
+ * For a description of parameters see check_acx().
+ */
+pub fn inherit_transitivity(index: usize, morpheme_list: &mut Morphemes) {
+    if index == 0 { return; }
+    let transitivity = match morpheme_list.get(index - 1) {
+        Some(previous_entry) if previous_entry.part_of_speech == POS::Verb ||
+                                previous_entry.part_of_speech == POS::SubstantiveVerb => {
+            previous_entry.transitivity
+        },
+        _ => return,
+    };
+    if let Some(current_entry) = morpheme_list.get_mut(index) {
+        current_entry.transitivity = transitivity;
+    }
+}  // inherit_transitivity
+
+
 /*
  * check_participle()
  *
@@ -711,6 +784,10 @@ fn valid_separator(pos: POS, index: usize, morpheme_list: &mut Morphemes) -> boo
  * Note: Passive participle endings can only be attached to transitive verbs.
  * Also, the participle ending is not necessarily the last morpheme in a word.
  * Words such as 'forges.it.aĵ.o' are sometimes found.
+ * If the participle is the last morpheme, it must be followed by a substantive,
+ * adjective or adverb ending: 'leg.ant.o', 'vid.ot.a', 'dir.it.e'. 'leg.ant.is'
+ * is invalid. The adverb ending makes an adverbial participle: 'leg.ant.e'
+ * (while reading), 'leg.int.e' (having read).
  * 
  * For a description of parameters see check_acx().
  */
@@ -741,6 +818,14 @@ fn check_participle(index: usize, morpheme_list: &mut Morphemes) -> bool {
         }
         else { return false; }
     }
+    else {
+        match morpheme_list.type_of_ending() {
+            POS::Substantive => (),
+            POS::Adjective => (),
+            POS::Adverb => (),
+            _ => return false,
+        }
+    }
 
     if previous_pos == POS::Verb || previous_pos == POS::SubstantiveVerb {
         if participle_string.len() == 2 {  // -it, -at, and -ot are passive participle endings.
@@ -777,19 +862,23 @@ fn check_participle(index: usize, morpheme_list: &mut Morphemes) -> bool {
  *
  * Params:
  *    morpheme list (vector of dictionary entries)
+ *    options (maximum number of separators)
  * Return:
  *    true for valid synthesis, false otherwise
  */
-pub fn scan_morphemes(morpheme_list: &mut Morphemes) -> bool {
+pub fn scan_morphemes(morpheme_list: &mut Morphemes, options: &Options) -> bool {
 
     let last = morpheme_list.last_index();
-    if morpheme_list.count_separators() > 1 { return false; }  // Only allow one.
+    if morpheme_list.count_separators() > options.max_separators {
+        return morpheme_list.fail(Invalidity::BadSynthesis { index: last });
+    }
 
     for index in 0..=last {
 
         let syn;
         let pos;
         let morpheme;
+        let bad_synthesis = Invalidity::BadSynthesis { index };
 
         if let Some(entry) = &morpheme_list.get(index) {
 
@@ -798,23 +887,119 @@ pub fn scan_morphemes(morpheme_list: &mut Morphemes) -> bool {
             morpheme = entry.word.clone();
 
             // Separator between morphemes (fingr-o-montri)
-            if entry.flag == Flag::Separator {
-                if !valid_separator(pos, index, morpheme_list) { return false; };
+            if entry.flag == Flag::Separator &&
+               !valid_separator(pos, index, morpheme_list) {
+                return morpheme_list.fail(bad_synthesis);
             }
         }
         else { return false; }  // Failure to get morpheme from vector.
 
         if syn == Synthesis::Prefix {
-            if index == last { return false; }   // A prefix can't be the last morpheme.
-            if !check_prefix(&morpheme, index, morpheme_list) { return false; }
+            if index == last {   // A prefix can't be the last morpheme.
+                return morpheme_list.fail(bad_synthesis);
+            }
+            if !check_prefix(&morpheme, index, morpheme_list) {
+                return morpheme_list.fail(bad_synthesis);
+            }
         }
         else if syn == Synthesis::Participle {
-            if !check_participle(index, morpheme_list) { return false; }
+            if !check_participle(index, morpheme_list) {
+                return morpheme_list.fail(bad_synthesis);
+            }
         }
-        else if syn == Synthesis::Limited {
-            if !check_limited_synthesis(&morpheme, index, morpheme_list) { return false; };
+        else if syn == Synthesis::Limited &&
+                !check_limited_synthesis(&morpheme, index, morpheme_list) {
+            return morpheme_list.fail(bad_synthesis);
         }
     }
     return true;  // All OK.
 
 } // scan_morphemes
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use super::super::vortaro::load_precompiled;
+
+    // 'bis' (encore) takes an ending, as in 'bis.i', and is a prefix for verbs only.
+    #[test]
+    fn prefix_bis() {
+        let dictionary = load_precompiled().unwrap();
+        let result = check_word("bisi", &dictionary);
+        assert!(result.valid);
+        assert_eq!(result.word, "bis.i");
+        let result = check_word("bisludis", &dictionary);
+        assert!(result.valid);
+        assert_eq!(result.word, "bis.lud.is");
+        assert!(!check_word("bislibro", &dictionary).valid);
+    }
+
+    // duon- comes before substantives and relatives. Before an adjective, and in
+    // 'duono' (a half), it is du.on.
+    #[test]
+    fn prefix_duon() {
+        let dictionary = load_precompiled().unwrap();
+        assert_eq!(check_word("duonhoro", &dictionary).word, "duon.hor.o");
+        assert_eq!(check_word("duonfrato", &dictionary).word, "duon.frat.o");
+        assert_eq!(check_word("duono", &dictionary).word, "du.on.o");
+        assert_eq!(check_word("duonbela", &dictionary).word, "du.on.bel.a");
+    }
+
+    // 'fi' (shameful) is a prefix before a root, and an interjection alone.
+    #[test]
+    fn prefix_fi() {
+        let dictionary = load_precompiled().unwrap();
+        assert_eq!(check_word("fihomo", &dictionary).word, "fi.hom.o");
+        assert_eq!(check_word("fiago", &dictionary).word, "fi.ag.o");
+        assert!(check_word("fi", &dictionary).valid);
+        assert!(!check_word("fiigi", &dictionary).valid);
+    }
+
+    // ĉef, mem and vic are roots, which combine freely.
+    #[test]
+    fn roots_used_as_prefixes() {
+        let dictionary = load_precompiled().unwrap();
+        for word in ["ĉefurbo", "ĉefredaktanto",
+                     "memstara", "memcerta", "memfido",
+                     "vicprezidanto", "vicordo"].iter() {
+            assert!(check_word(word, &dictionary).valid, "{}", word);
+        }
+    }
+
+    #[test]
+    fn participle_nouns() {
+        let dictionary = load_precompiled().unwrap();
+        assert_eq!(check_word("batalinto", &dictionary).word, "batal.int.o");
+        assert_eq!(check_word("vidantino", &dictionary).word, "vid.ant.in.o");
+        assert!(check_word("vidantino", &dictionary).valid);
+    }
+
+    // A participle which ends the word takes a substantive, adjective or adverb
+    // ending, not a verb ending.
+    #[test]
+    fn participle_endings() {
+        let dictionary = load_precompiled().unwrap();
+        for word in ["farantus", "farintis", "legontas"].iter() {
+            assert!(!check_word(word, &dictionary).valid, "{}", word);
+        }
+        assert_eq!(check_word("faranta", &dictionary).word, "far.ant.a");
+        assert_eq!(check_word("farinte", &dictionary).word, "far.int.e");
+        assert_eq!(check_word("legonto", &dictionary).word, "leg.ont.o");
+    }
+
+    // The participle takes the transitivity of its verb, so that the suffixes
+    // which follow it can check it. (See inherit_transitivity().)
+    #[test]
+    fn participle_transitivity() {
+        let dictionary = load_precompiled().unwrap();
+        let result = check_word("frapitaĵo", &dictionary);
+        assert!(result.valid);
+        assert_eq!(result.word, "frap.it.aĵ.o");
+        assert_eq!(result.morphemes[1].transitivity, Transitivity::Transitive);
+        let result = check_word("dormanto", &dictionary);
+        assert_eq!(result.morphemes[1].transitivity, Transitivity::Intransitive);
+        assert!(!check_word("dormitaĵo", &dictionary).valid);
+    }
+
+}  // tests
@@ -0,0 +1,199 @@
+// spell_checker - This module has a spell checker which owns its dictionary.
+// Klivo 2020-05-24
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use super::entry::*;
+use super::vortaro::*;
+use super::check_word::*;
+use super::tokenizer::*;
+use super::x_to_accent;
+
+/*
+ * SpellChecker
+ * A spell checker and morphological analyzer, with its dictionary.
+ * Fields:
+ *     'dictionary' - the dictionary of morphemes
+ *     'allowlist' - whole words which are always valid, in lower case, eg. 'zamenhof'
+ *     'options' - settings for check_word_with_options(), eg. the map of exceptions
+ */
+pub struct SpellChecker {
+    dictionary: Dictionary,
+    allowlist: HashSet<String>,
+    options: Options,
+}
+
+// Normalizes a word for the allowlist and the exceptions: lower case, with accented
+// letters instead of 'x'.
+fn allowlist_form(word: &str) -> String {
+    return x_to_accent(&word.to_lowercase());
+}
+
+impl SpellChecker {
+
+    /*
+     * from_embedded()
+     *
+     * Makes a spell checker with the dictionary which is embedded in the library.
+     *
+     * Return:
+     *    spell checker, or an error if the embedded dictionary is invalid
+     */
+    pub fn from_embedded() -> Result<SpellChecker, DictError> {
+        let dictionary = load_precompiled()?;
+        return Ok(SpellChecker { dictionary, allowlist: HashSet::new(),
+                                 options: Options::default() });
+    }
+
+    /*
+     * from_tsv()
+     *
+     * Makes a spell checker from dictionary data, in the format of vortaro.tsv.
+     *
+     * Params:
+     *    dictionary data
+     * Return:
+     *    spell checker, or an error for the first bad row
+     */
+    pub fn from_tsv(data: &str) -> Result<SpellChecker, DictError> {
+        let dictionary = make_dictionary(data)?;
+        return Ok(SpellChecker { dictionary, allowlist: HashSet::new(),
+                                 options: Options::default() });
+    }
+
+    /*
+     * with_allowlist()
+     *
+     * Adds words which are always valid, such as names ('Bjalistoko'). Unlike the
+     * roots of add_word(), these are whole words. They are not analyzed, and
+     * match only the same word, ignoring case. Eg. 'Bjalistoko' allows 'BJALISTOKO',
+     * but not 'Bjalistokon'.
+     *
+     * Params:
+     *    words
+     * Return:
+     *    spell checker
+     */
+    pub fn with_allowlist(mut self, words: &[&str]) -> SpellChecker {
+        for word in words {
+            self.allowlist.insert(allowlist_form(word));
+        }
+        return self;
+    }
+
+    /*
+     * with_exceptions()
+     *
+     * Replaces the map of exceptions: words which are divided from the map, before
+     * the dictionary is searched. The keys are words, and the values are their
+     * divisions, eg. 'vin' -> 'vi.n'. The last part of a division is its ending.
+     * Extend default_exceptions() to keep the accusative pronouns.
+     *
+     * Params:
+     *    map of exceptions
+     * Return:
+     *    spell checker
+     */
+    pub fn with_exceptions(mut self, exceptions: HashMap<String, String>) -> SpellChecker {
+        let exceptions = exceptions.iter()
+            .map(|(word, division)| (allowlist_form(word), allowlist_form(division)))
+            .collect();
+        self.options.exceptions = Some(exceptions);
+        return self;
+    }
+
+    /*
+     * check()
+     *
+     * Checks the spelling of a word, and divides it into morphemes.
+     * Words of the allowlist are valid, and are not divided.
+     *
+     * Params:
+     *    word
+     * Return:
+     *    analysis result
+     */
+    pub fn check(&self, word: &str) -> AnalysisResult {
+        if !self.allowlist.is_empty() && self.allowlist.contains(&allowlist_form(word)) {
+            return AnalysisResult::new(word, word, true);
+        }
+        return check_word_with_options(word, &self.dictionary, &self.options);
+    }
+
+    /*
+     * add_word()
+     *
+     * Adds a word to the dictionary, or replaces the entry which has the same key.
+     * The key is made as for make_dictionary(), so 'komputil' makes 'komputilo' valid.
+     *
+     * Params:
+     *    root, eg. 'komputil' or 'kamarad.an'
+     *    part of speech
+     *    transitivity
+     */
+    pub fn add_word(&mut self, root: &str, pos: POS, transitivity: Transitivity) {
+        let key = make_key(root, pos);
+        self.dictionary.insert(key, Entry::new_root(root, pos, transitivity));
+    }
+
+    /*
+     * remove_word()
+     *
+     * Removes a word from the dictionary.
+     *
+     * Params:
+     *    root, eg. 'komputil'
+     * Return:
+     *    true if the word was in the dictionary
+     */
+    pub fn remove_word(&mut self, root: &str) -> bool {
+        if self.dictionary.remove(&make_key(root, POS::Substantive)).is_some() {
+            return true;
+        }
+        // Abbreviations which end with a period keep it in their keys.
+        return self.dictionary.remove(&make_key(root, POS::Abbreviation)).is_some();
+    }
+
+    /*
+     * roots_with_prefix()
+     *
+     * Finds the dictionary keys which begin with a prefix, eg. for type-ahead
+     * completion of a partial root. The prefix is converted to lower case, and
+     * 'x' can represent accents.
+     *
+     * Params:
+     *    prefix, eg. 'muzik' or 'cxiu'
+     * Return:
+     *    list of keys, sorted
+     */
+    pub fn roots_with_prefix(&self, prefix: &str) -> Vec<&str> {
+        let prefix = x_to_accent(&prefix.to_lowercase());
+        return self.dictionary.starting_with(&prefix);
+    }
+
+    /*
+     * analyze_text()
+     *
+     * Divides the words of a text into morphemes. Characters between words
+     * are unchanged.
+     *
+     * Params:
+     *    text
+     * Return:
+     *    analyzed text, eg. 'La elefant.oj'
+     */
+    pub fn analyze_text(&self, text: &str) -> String {
+        let mut analyzed = String::new();
+        for token in tokenize(text) {
+            if token.is_word {
+                analyzed.push_str(&self.check(token.text).word);
+            }
+            else {
+                analyzed.push_str(token.text);
+            }
+        }
+        return analyzed;
+    }
+
+}  // impl SpellChecker
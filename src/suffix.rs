@@ -147,8 +147,7 @@ fn check_an(index: usize, morpheme_list: &mut Morphemes) -> bool {
         let pos = previous_entry.part_of_speech;
         let meaning = previous_entry.meaning;
         if  pos <= POS::SubstantiveVerb {
-            if is_person(meaning) { return false; }
-            else { return true;}
+            return !is_person(meaning);
         }
     }
     return false;
@@ -181,6 +180,8 @@ fn check_ar(index: usize, morpheme_list: &mut Morphemes) -> bool {
  *
  * Check suffix -ebl, meaning 'capable of being verb-ed'.
  * This suffix is generally attached to transitive verbs.
+ * The transitivity of the previous morpheme may have been set by a suffix,
+ * so 'manĝ.ig.ebl.a' is accepted and 'ruĝ.iĝ.ebl.a' is not. (See check_ig_igx().)
  *
  * For a description of parameters see check_acx().
  */
@@ -188,9 +189,8 @@ fn check_ebl(index: usize, morpheme_list: &mut Morphemes) -> bool {
     if index == 0 { return true; }
     if let Some(previous_entry) = morpheme_list.get(index - 1) {
         let pos = previous_entry.part_of_speech;
-        if  pos == POS::Verb || pos == POS::SubstantiveVerb {
-            if previous_entry.transitivity == Transitivity::Transitive { return true; }
-        }
+        if (pos == POS::Verb || pos == POS::SubstantiveVerb) &&
+           previous_entry.transitivity == Transitivity::Transitive { return true; }
     }
     return false;
 }  // check_ebl
@@ -311,6 +311,38 @@ fn check_em(index: usize, morpheme_list: &mut Morphemes) -> bool {
 }  // check_em
 
 
+/*
+ * check_um()
+ *
+ * Check suffix -um, which has no definite meaning. Eg. kol-um-o (collar),
+ * plen-um-i (fulfill), aer-um-i (air out). Because its meaning is vague, -um is
+ * permissive. It can follow substantives, adjectives, verbs and adverbs, and the
+ * resulting word can take any ending. Its transitivity does not depend on the
+ * previous morpheme, so it is not transferred.
+ *
+ * For a description of parameters see check_acx().
+ */
+fn check_um(index: usize, morpheme_list: &mut Morphemes) -> bool {
+
+    if index == 0 { return false; }
+    let pos: POS;   // Part of Speech
+
+    if let Some(previous_entry) = morpheme_list.get(index - 1) {
+        pos = previous_entry.part_of_speech;
+    }
+    else { return false; }
+
+    if let Some(current_entry) = morpheme_list.get_mut(index) {
+        if  pos <= POS::Adverb {
+            current_entry.part_of_speech = POS::SubstantiveVerb;
+            return true;
+        }
+    }
+    return false;
+
+}  // check_um
+
+
 /*
  * check_end_ind()
  *
@@ -318,7 +350,8 @@ fn check_em(index: usize, morpheme_list: &mut Morphemes) -> bool {
  * -ind means, worthy to be (verb)-ed. Eg. vid-ind-a , worthy to be seen.
  * -end means, required to be (verb)-ed. Eg. pag-end-a, necessary to be paid.
  * These suffixes are normally only applied to transitive verbs. Mir-ind-a is
- * an exception.
+ * an exception. As for -ebl, the transitivity of the previous morpheme may have
+ * been set by a suffix: 'dorm.ig.ind.a' is accepted, 'ruĝ.iĝ.ind.a' is not.
  *
  * For a description of parameters see check_acx().
  */
@@ -438,18 +471,38 @@ fn check_id(index: usize, morpheme_list: &mut Morphemes) -> bool {
  * Check suffixes -ig and -iĝ.
  * -ig is a causative suffix, eg. 'star-ig-is' (make to stand).
  * -iĝ indicates a change of state, eg. 'griz-iĝ-is' (became grey).
- * 
+ *
+ * The result is a verb. With -ig it is transitive, with -iĝ it is intransitive,
+ * regardless of the previous morpheme. This matters for suffixes which follow,
+ * such as -ebl. Eg. 'san-ig-ebl-a' (curable).
+ *
  * For a description of parameters see check_acx().
  */
 fn check_ig_igx(index: usize, morpheme_list: &mut Morphemes) -> bool {
+
     if index == 0 { return false; }
+    let pos: POS;   // Part of Speech
+
     if let Some(previous_entry) = morpheme_list.get(index - 1) {
-        let pos = previous_entry.part_of_speech;
+        pos = previous_entry.part_of_speech;
+    }
+    else { return false; }
+
+    if let Some(current_entry) = morpheme_list.get_mut(index) {
         if  pos <= POS::Adverb ||
             pos == POS::Preposition ||
-            pos == POS::Prefix { return true; }
+            pos == POS::Prefix {
+            current_entry.transitivity = if current_entry.word == "ig" {
+                Transitivity::Transitive
+            } else {
+                Transitivity::Intransitive
+            };
+            current_entry.part_of_speech = POS::Verb;
+            return true;
+        }
     }
     return false;
+
 }  // check_ig_igx
 
 
@@ -513,18 +566,70 @@ fn check_ist(index: usize, morpheme_list: &mut Morphemes) -> bool {
  *
  * Check suffixes -obl, -on, -op. These are attached to numbers.
  * Eg. du-obl-e (double), du-on-o (a half), du-op-o (a pair).
- * 
+ *
+ * -on and -op make substantives, and -obl makes an adjective, so that other
+ * suffixes can follow. Eg. 'du-on-ig-i' (to halve).
+ *
  * For a description of parameters see check_acx().
  */
 fn check_obl_on_op(index: usize, morpheme_list: &mut Morphemes) -> bool {
+
     if index == 0 { return false; }
+    let pos: POS;   // Part of Speech
+
     if let Some(previous_entry) = morpheme_list.get(index - 1) {
-        if  previous_entry.part_of_speech == POS::Number { return true; }
+        pos = previous_entry.part_of_speech;
+    }
+    else { return false; }
+
+    if let Some(current_entry) = morpheme_list.get_mut(index) {
+        if  pos == POS::Number {
+            current_entry.part_of_speech = if current_entry.word == "obl" {
+                POS::Adjective
+            } else {
+                POS::Substantive
+            };
+            return true;
+        }
     }
     return false;
+
 }  // check_obl_on_op
 
 
+/*
+ * check_io()
+ *
+ * Check suffix -io (-i plus the ending -o), which forms the name of a country
+ * from an ethnic root or the name of its people. Eg. Meksik-i-o, Angl-i-o (England).
+ * The result has the meaning 'country' (Lando).
+ *
+ * Note: This suffix is only tried when dividing compound words, because
+ * '-i' is also the verb ending.
+ *
+ * For a description of parameters see check_acx().
+ */
+fn check_io(index: usize, morpheme_list: &mut Morphemes) -> bool {
+
+    if index == 0 { return false; }
+    let meaning: Meaning;
+
+    if let Some(previous_entry) = morpheme_list.get(index - 1) {
+        meaning = previous_entry.meaning;
+    }
+    else { return false; }
+
+    if let Some(current_entry) = morpheme_list.get_mut(index) {
+        if is_person(meaning) || meaning == Meaning::Etno {
+            current_entry.meaning = Meaning::Lando;
+            return true;
+        }
+    }
+    return false;
+
+}  // check_io
+
+
 /*
  * check_uj()
  *
@@ -602,6 +707,7 @@ pub fn check_suffix(_suffix: &str, index: usize, morpheme_list: &mut Morphemes)
         "ing" => return check_ik_ing_ism(index, morpheme_list),
         "ism" => return check_ik_ing_ism(index, morpheme_list),
         "estr" => return check_estr(index, morpheme_list),
+        "i" => return check_io(index, morpheme_list),
         "id" => return check_id(index, morpheme_list),
         "ig" => return check_ig_igx(index, morpheme_list),
         "iĝ" => return check_ig_igx(index, morpheme_list),
@@ -613,6 +719,37 @@ pub fn check_suffix(_suffix: &str, index: usize, morpheme_list: &mut Morphemes)
         "op" => return check_obl_on_op(index, morpheme_list),
         "uj" => return check_uj(index, morpheme_list),
         "ul" => return check_ul(index, morpheme_list),
+        "um" => return check_um(index, morpheme_list),
         _ => return false,
     }
 }  // check_suffix
+
+#[cfg(test)]
+mod tests {
+
+    use super::super::check_word::check_word;
+    use super::super::vortaro::load_precompiled;
+
+    // -ebl, -ind and -end need a transitive verb, including one made by -ig.
+    #[test]
+    fn suffixes_after_ig_and_igx() {
+        let dictionary = load_precompiled().unwrap();
+        let result = check_word("manĝigebla", &dictionary);
+        assert!(result.valid);
+        assert_eq!(result.word, "manĝ.ig.ebl.a");
+        assert!(!check_word("ruĝiĝebla", &dictionary).valid);
+        assert!(check_word("manĝigenda", &dictionary).valid);
+        assert!(!check_word("ruĝiĝinda", &dictionary).valid);
+    }
+
+    // -um follows substantives, adjectives, verbs and adverbs, not prepositions.
+    #[test]
+    fn suffix_um() {
+        let dictionary = load_precompiled().unwrap();
+        assert_eq!(check_word("plenumi", &dictionary).word, "plen.um.i");
+        assert_eq!(check_word("aerumi", &dictionary).word, "aer.um.i");
+        assert_eq!(check_word("kolumo", &dictionary).word, "kol.um.o");
+        assert!(!check_word("ĝisumi", &dictionary).valid);
+    }
+
+}  // tests
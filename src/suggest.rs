@@ -0,0 +1,285 @@
+// suggest - This module offers corrections for misspelled words.
+// Klivo 2020-05-20
+
+use std::collections::HashSet;
+
+use super::*;
+
+use super::check_word::*;
+
+// Lower case letters of the Esperanto alphabet.
+const ALPHABET: &str = "abcĉdefgĝhĥijĵklmnoprsŝtuŭvz";
+
+// The maximum number of letters which hat_variants() will try to accent.
+// (Each letter doubles the number of variants.)
+const MAX_HAT_LETTERS: usize = 10;
+
+// The maximum number of doubled letters which undoubled_variants() will try to reduce.
+const MAX_DOUBLED_LETTERS: usize = 8;
+
+/*
+ * single_edits()
+ *
+ * Makes all words which are one edit away from the given word:
+ * deletions, transpositions, substitutions and insertions of Esperanto letters.
+ *
+ * Params:
+ *    word (lower case)
+ * Return:
+ *    set of edited words
+ */
+fn single_edits(word: &str) -> HashSet<String> {
+
+    let chars: Vec<char> = word.chars().collect();
+    let length = chars.len();
+    let mut edits: HashSet<String> = HashSet::new();
+
+    for i in 0..length {
+        // Deletion
+        let mut w = chars.clone();
+        w.remove(i);
+        edits.insert(w.into_iter().collect());
+        // Transposition
+        if i + 1 < length {
+            let mut w = chars.clone();
+            w.swap(i, i + 1);
+            edits.insert(w.into_iter().collect());
+        }
+        // Substitution
+        for letter in ALPHABET.chars() {
+            if letter == chars[i] { continue; }
+            let mut w = chars.clone();
+            w[i] = letter;
+            edits.insert(w.into_iter().collect());
+        }
+    }
+
+    // Insertion
+    for i in 0..=length {
+        for letter in ALPHABET.chars() {
+            let mut w = chars.clone();
+            w.insert(i, letter);
+            edits.insert(w.into_iter().collect());
+        }
+    }
+
+    edits.remove(word);
+    return edits;
+
+}  // single_edits
+
+
+/*
+ * hat_variants()
+ *
+ * Makes all variants of a word which have 'hats' on some of the letters
+ * c, g, h, j, s and u, eg. 'sanco' -> 'ŝanco', 'sanĉo', 'ŝanĉo'.
+ *
+ * Params:
+ *    word (lower case)
+ * Return:
+ *    list of variants, not including the word itself
+ */
+fn hat_variants(word: &str) -> Vec<String> {
+
+    let chars: Vec<char> = word.chars().collect();
+    let positions: Vec<usize> = (0..chars.len()).filter(|&i| accepts_hat!(chars[i])).collect();
+    if positions.len() > MAX_HAT_LETTERS { return vec![]; }
+
+    let mut variants = vec![];
+    for combination in 1..(1usize << positions.len()) {
+        let mut w = chars.clone();
+        for (bit, &i) in positions.iter().enumerate() {
+            if combination & (1 << bit) != 0 { w[i] = accent_letter!(w[i]); }
+        }
+        variants.push(w.into_iter().collect());
+    }
+    return variants;
+
+}  // hat_variants
+
+
+/*
+ * undoubled_variants()
+ *
+ * Makes all variants of a word in which some doubled letters are reduced to
+ * one letter, eg. 'konstruuii' -> 'konstruii', 'konstruui', 'konstrui'.
+ * A doubled letter is a common typing error, and two of them are two edits
+ * away from the correct word, too far for single_edits().
+ *
+ * Params:
+ *    word (lower case)
+ * Return:
+ *    list of variants, not including the word itself
+ */
+fn undoubled_variants(word: &str) -> Vec<String> {
+
+    let chars: Vec<char> = word.chars().collect();
+    let positions: Vec<usize> = (1..chars.len()).filter(|&i| chars[i] == chars[i - 1]).collect();
+    if positions.len() > MAX_DOUBLED_LETTERS { return vec![]; }
+
+    let mut variants: Vec<String> = vec![];
+    for combination in 1..(1usize << positions.len()) {
+        let mut w = chars.clone();
+        for (bit, &i) in positions.iter().enumerate().rev() {
+            if combination & (1 << bit) != 0 { w.remove(i); }
+        }
+        let variant: String = w.into_iter().collect();
+        if !variants.contains(&variant) { variants.push(variant); }
+    }
+    return variants;
+
+}  // undoubled_variants
+
+
+/*
+ * rarity_of()
+ *
+ * Gets the rarity of an analyzed word, which is the rarity
+ * of its rarest morpheme. (0 is common, 4 is rare.)
+ *
+ * Params:
+ *    analysis result
+ * Return:
+ *    rarity
+ */
+fn rarity_of(result: &AnalysisResult) -> usize {
+    return result.rarity.unwrap_or(0);
+}
+
+
+/*
+ * suggest_hats()
+ *
+ * Suggests corrections for a word which is missing 'hats', such as 'cirkau'
+ * for 'ĉirkaŭ'. Every combination of hats on the letters c, g, h, j, s and u
+ * is checked, and the valid ones are returned, common words first.
+ *
+ * Params:
+ *    word (accents may be represented by x)
+ *    dictionary - a map of word data
+ * Return:
+ *    list of suggestions
+ */
+pub fn suggest_hats(word: &str, dictionary: &Dictionary) -> Vec<String> {
+
+    let normalized = x_to_accent(word).to_lowercase();
+    let capitalized = word.chars().next().is_some_and(|ch| ch.is_uppercase());
+
+    let mut candidates: Vec<(usize, String)> = vec![];
+    for candidate in hat_variants(&normalized) {
+        let result = check_word(&candidate, dictionary);
+        if result.valid {
+            candidates.push((rarity_of(&result), candidate));
+        }
+    }
+
+    candidates.sort();
+
+    let mut suggestions: Vec<String> = vec![];
+    for (_, candidate) in candidates {
+        if capitalized { suggestions.push(capitalize(&candidate)); }
+        else { suggestions.push(candidate); }
+    }
+    return suggestions;
+
+}  // suggest_hats
+
+
+/*
+ * suggest()
+ *
+ * Suggests corrections for a misspelled word. A word in the x-system, such as
+ * 'gxoji', is first suggested with its accents: 'ĝoji'. Words which differ only by
+ * missing hats (see suggest_hats()) come first, because forgetting a hat is
+ * the most common mistake. Letters which were typed twice, as in 'konstruuii'
+ * for 'konstrui', are also corrected first (see undoubled_variants()). Other
+ * candidates are words one edit away from the given word (see single_edits()),
+ * which are valid according to check_word(). A transposition, as in 'mnaĝas'
+ * for 'manĝas', counts as one edit. Candidates at the same distance are sorted
+ * by rarity, so that common words come first, and then by the number of
+ * morphemes, so that 'manĝ.as' comes before 'an.aĝ.as'.
+ *
+ * Params:
+ *    word - the misspelled word (accents may be represented by x)
+ *    dictionary - a map of word data
+ *    max - the maximum number of suggestions
+ * Return:
+ *    list of suggestions
+ */
+pub fn suggest(word: &str, dictionary: &Dictionary, max: usize) -> Vec<String> {
+
+    if max == 0 || word.is_empty() { return vec![]; }
+
+    let normalized = x_to_accent(word).to_lowercase();
+    let capitalized = word.chars().next().is_some_and(|ch| ch.is_uppercase());
+
+    // The accented word, hat variants and undoubled variants are sorted before all
+    // other candidates (distance 0). single_edits() makes only candidates at distance 1.
+    // A word in the x-system, eg. 'gxoji', is first suggested with its accents.
+    let accented = Some(normalized.clone()).filter(|w| *w != word.to_lowercase());
+    let hats_and_doubles = accented.into_iter().chain(hat_variants(&normalized))
+        .chain(undoubled_variants(&normalized)).map(|candidate| (0, candidate));
+    let edits = single_edits(&normalized).into_iter().map(|candidate| (1, candidate));
+
+    let mut checked: HashSet<String> = HashSet::new();
+    let mut candidates: Vec<(usize, usize, usize, String)> = vec![];
+    for (distance, candidate) in hats_and_doubles.chain(edits) {
+        if !checked.insert(candidate.clone()) { continue; }
+        let result = check_word(&candidate, dictionary);
+        if result.valid {
+            candidates.push((distance, rarity_of(&result), result.morphemes.len(), candidate));
+        }
+    }
+
+    candidates.sort();
+
+    let mut suggestions: Vec<String> = vec![];
+    for (_, _, _, candidate) in candidates.into_iter().take(max) {
+        if capitalized { suggestions.push(capitalize(&candidate)); }
+        else { suggestions.push(candidate); }
+    }
+    return suggestions;
+
+}  // suggest
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use super::super::vortaro::load_precompiled;
+
+    #[test]
+    fn suggest_transpositions() {
+        let dictionary = load_precompiled().unwrap();
+        assert!(suggest("mnaĝas", &dictionary, 3).contains(&String::from("manĝas")));
+        assert!(suggest("lenras", &dictionary, 3).contains(&String::from("lernas")));
+    }
+
+    #[test]
+    fn missing_hats() {
+        let dictionary = load_precompiled().unwrap();
+        assert_eq!(suggest_hats("cirkau", &dictionary), vec!["ĉirkaŭ"]);
+        assert_eq!(suggest_hats("Cxirkau", &dictionary), vec!["Ĉirkaŭ"]);
+        assert!(suggest_hats("sangxo", &dictionary).contains(&String::from("ŝanĝo")));
+        assert!(suggest_hats("domo", &dictionary).is_empty());
+        assert_eq!(suggest("cirkau", &dictionary, 3)[0], "ĉirkaŭ");
+    }
+
+    #[test]
+    fn doubled_letters() {
+        assert_eq!(undoubled_variants("konstruuii"), vec!["konstruii", "konstruui", "konstrui"]);
+        assert!(undoubled_variants("domo").is_empty());
+        let dictionary = load_precompiled().unwrap();
+        assert_eq!(suggest("konstruuii", &dictionary, 3)[0], "konstrui");
+        assert_eq!(suggest("Mannĝas", &dictionary, 3)[0], "Manĝas");
+    }
+
+    #[test]
+    fn suggest_x_system() {
+        let dictionary = load_precompiled().unwrap();
+        assert_eq!(suggest("gxoji", &dictionary, 3)[0], "ĝoji");
+        assert_eq!(suggest("Sxangxo", &dictionary, 3)[0], "Ŝanĝo");
+    }
+
+}  // tests
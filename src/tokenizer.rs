@@ -0,0 +1,184 @@
+// tokenizer - This module divides a text into words, and the characters between them.
+// Klivo 2020-05-24
+
+/*
+ * Token
+ * A word, or a run of characters between words.
+ * Fields:
+ *     'offset' is the byte offset of the token in the text.
+ *     'text' is the token itself.
+ *     'is_word' is true for words, false for the characters between words.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Token<'a> {
+    pub offset: usize,
+    pub text: &'a str,
+    pub is_word: bool,
+}
+
+
+/*
+ * abbreviation_period()
+ *
+ * Determines whether a period belongs to an abbreviation, such as 'k.t.p.' or 't.e.'.
+ * A period which follows a single letter is kept as part of the word if another
+ * single letter follows it, or if the word already has a period (final period).
+ *
+ * Params:
+ *     word collected so far
+ *     characters which follow the period
+ * Return:
+ *     true if the period is part of the word
+ */
+fn abbreviation_period(collected: &str, following: &[(usize, char)]) -> bool {
+
+    let last_segment = collected.rsplit('.').next().unwrap_or("");
+    let mut letters = last_segment.chars();
+    let single_letter = match (letters.next(), letters.next()) {
+        (Some(ch), None) => ch.is_alphabetic(),
+        _ => false,
+    };
+    if !single_letter { return false; }
+
+    if let Some(&(_, next)) = following.first() {
+        if next.is_alphabetic() {
+            // Another single letter must follow, as in 'k.t'.
+            return match following.get(1) {
+                Some(&(_, after)) => !is_word_char!(after),
+                None => true,
+            };
+        }
+    }
+    return collected.contains('.');
+
+}  // abbreviation_period()
+
+
+/*
+ * word_length()
+ *
+ * Apostrophes at the end of a word are quotation marks, except for one which
+ * follows a letter, because it marks an elision, as in 'l'' and 'dom''.
+ *
+ * Params:
+ *     word, which does not begin with an apostrophe
+ * Return:
+ *     length of the word in bytes, without the quotation marks
+ */
+fn word_length(word: &str) -> usize {
+    let trimmed = word.trim_end_matches(|ch: char| is_apostrophe!(ch));
+    if trimmed.len() == word.len() { return word.len(); }
+    match trimmed.chars().last() {
+        Some(ch) if ch.is_alphabetic() => {
+            let apostrophe = word[trimmed.len()..].chars().next().map_or(0, |ch| ch.len_utf8());
+            return trimmed.len() + apostrophe;
+        },
+        _ => return trimmed.len(),
+    }
+}  // word_length()
+
+
+/*
+ * tokenize()
+ *
+ * Divides a text into words and the runs of characters between them.
+ * Word characters are defined by is_word_char!, so hyphens, soft hyphens and
+ * apostrophes are part of words, but apostrophes at the start of a word, and
+ * at its end (see word_length()), are quotation marks. A digit after letters
+ * begins a new word, so digits are not swept into alphabetic words, but letters
+ * after digits belong to the number, as in the ordinal '3-a'. Periods are kept
+ * in abbreviations such as 'k.t.p.'.
+ * The tokens are in order, and their slices joined together give back the text.
+ * They need not alternate: 'abc3' gives two adjacent words, 'abc' and '3'.
+ *
+ * Params:
+ *     text
+ * Return:
+ *     tokens, in order
+ */
+pub fn tokenize(text: &str) -> Vec<Token<'_>> {
+
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut tokens = vec![];
+
+    let mut start = 0;
+    let mut in_word = false;
+    let mut in_number = false;   // The current word begins with a digit, eg. '3-a'.
+
+    for (i, &(offset, ch)) in chars.iter().enumerate() {
+
+        if in_word && ch == '.' && abbreviation_period(&text[start..offset], &chars[i + 1..]) {
+            continue;
+        }
+
+        let is_digit = ch.is_ascii_digit();
+        // An apostrophe at the start of a word is a quotation mark.
+        let starts_word = !in_word && is_apostrophe!(ch);
+        if (is_word_char!(ch) && !starts_word) || is_digit {
+            // Digits are not swept into alphabetic words.
+            let new_word = !in_word || (is_digit && !in_number);
+            if new_word {
+                if in_word { start = push_word(&mut tokens, text, start, offset); }
+                if offset > start {
+                    tokens.push(Token { offset: start, text: &text[start..offset], is_word: false });
+                }
+                start = offset;
+                in_number = is_digit;
+            }
+            in_word = true;
+        }
+        else {
+            if in_word { start = push_word(&mut tokens, text, start, offset); }
+            in_word = false;
+        }
+    }
+
+    if in_word { start = push_word(&mut tokens, text, start, text.len()); }
+    if text.len() > start {
+        tokens.push(Token { offset: start, text: &text[start..], is_word: false });
+    }
+
+    return tokens;
+
+}  // tokenize()
+
+// Pushes the word from 'start' to 'end', without its final quotation marks,
+// and returns the offset of the characters which follow it.
+fn push_word<'a>(tokens: &mut Vec<Token<'a>>, text: &'a str, start: usize, end: usize) -> usize {
+    let end_of_word = start + word_length(&text[start..end]);
+    tokens.push(Token { offset: start, text: &text[start..end_of_word], is_word: true });
+    return end_of_word;
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn texts(text: &str) -> Vec<(&str, bool)> {
+        return tokenize(text).iter().map(|token| (token.text, token.is_word)).collect();
+    }
+
+    #[test]
+    fn words_and_numbers() {
+        assert_eq!(texts("ruĝa, bela domo"),
+                   vec![("ruĝa", true), (", ", false), ("bela", true), (" ", false), ("domo", true)]);
+        assert_eq!(texts("abc3"), vec![("abc", true), ("3", true)]);
+        assert_eq!(texts("la 3-a k.t.p."),
+                   vec![("la", true), (" ", false), ("3-a", true), (" ", false), ("k.t.p.", true)]);
+        let text = "Ĉu 12abc? ekz. abc3";
+        let joined: String = tokenize(text).iter().map(|token| token.text).collect();
+        assert_eq!(joined, text);
+    }
+
+    #[test]
+    fn apostrophes() {
+        assert_eq!(texts("de l' mond'"),
+                   vec![("de", true), (" ", false), ("l'", true), (" ", false), ("mond'", true)]);
+        assert_eq!(texts("'domo'' ' ''"),
+                   vec![("'", false), ("domo'", true), ("' ' ''", false)]);
+        assert_eq!(texts("l'amiko"), vec![("l'amiko", true)]);
+        assert_eq!(texts("3'"), vec![("3", true), ("'", false)]);
+    }
+
+}  // tests
@@ -0,0 +1,143 @@
+// trie - This module has a trie (prefix tree) of the dictionary's keys.
+// The trie allows find_morpheme() to find every morpheme which begins a word,
+// in one pass over the word, instead of looking up each possible length.
+// Klivo 2020-05-26
+
+/*
+ * Node
+ * Fields:
+ *     'children' are pairs of a character and the index of the child node.
+ *     'terminal' is true if a key ends at this node.
+ */
+#[derive(Debug, Clone)]
+struct Node {
+    children: Vec<(char, usize)>,
+    terminal: bool,
+}
+
+impl Node {
+    fn new() -> Node {
+        return Node { children: vec![], terminal: false };
+    }
+
+    fn child(&self, ch: char) -> Option<usize> {
+        for &(c, index) in &self.children {
+            if c == ch { return Some(index); }
+        }
+        return None;
+    }
+}
+
+/*
+ * Trie
+ * The nodes are kept in a vector. The root node is at index 0.
+ */
+#[derive(Debug, Clone)]
+pub struct Trie {
+    nodes: Vec<Node>,
+}
+
+impl Trie {
+
+    pub fn new() -> Trie {
+        return Trie { nodes: vec![Node::new()] };
+    }
+
+    /*
+     * insert()
+     *
+     * Params:
+     *    key
+     */
+    pub fn insert(&mut self, key: &str) {
+        let mut current = 0;
+        for ch in key.chars() {
+            current = match self.nodes[current].child(ch) {
+                Some(index) => index,
+                None => {
+                    let index = self.nodes.len();
+                    self.nodes.push(Node::new());
+                    self.nodes[current].children.push((ch, index));
+                    index
+                }
+            };
+        }
+        self.nodes[current].terminal = true;
+    }
+
+    /*
+     * remove()
+     *
+     * Removes a key. The nodes remain, but no longer mark the end of a key.
+     *
+     * Params:
+     *    key
+     */
+    pub fn remove(&mut self, key: &str) {
+        let mut current = 0;
+        for ch in key.chars() {
+            match self.nodes[current].child(ch) {
+                Some(index) => current = index,
+                None => return,
+            }
+        }
+        self.nodes[current].terminal = false;
+    }
+
+    /*
+     * prefixes()
+     *
+     * Finds the keys which are prefixes of the given word.
+     *
+     * Params:
+     *    word
+     * Return:
+     *    list of prefixes, as (length in bytes, length in characters), shortest first
+     */
+    pub fn prefixes(&self, word: &str) -> Vec<(usize, usize)> {
+        let mut found = vec![];
+        let mut current = 0;
+        for (count, (offset, ch)) in word.char_indices().enumerate() {
+            match self.nodes[current].child(ch) {
+                Some(index) => current = index,
+                None => break,
+            }
+            if self.nodes[current].terminal {
+                found.push((offset + ch.len_utf8(), count + 1));
+            }
+        }
+        return found;
+    }
+
+    /*
+     * starting_with()
+     *
+     * Finds the keys which begin with the given prefix, including the prefix itself.
+     *
+     * Params:
+     *    prefix
+     * Return:
+     *    list of keys, in no particular order
+     */
+    pub fn starting_with(&self, prefix: &str) -> Vec<String> {
+        let mut current = 0;
+        for ch in prefix.chars() {
+            match self.nodes[current].child(ch) {
+                Some(index) => current = index,
+                None => return vec![],
+            }
+        }
+        let mut found = vec![];
+        let mut stack = vec![(current, String::from(prefix))];
+        while let Some((node, key)) = stack.pop() {
+            if self.nodes[node].terminal { found.push(key.clone()); }
+            for &(ch, child) in &self.nodes[node].children {
+                let mut longer = key.clone();
+                longer.push(ch);
+                stack.push((child, longer));
+            }
+        }
+        return found;
+    }
+
+}  // impl Trie
@@ -2,14 +2,274 @@
 // Klivo 2020-05-08
 
 use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
 
-use literumilo::*;
+use super::*;
 use super::entry::*;
+use super::trie::*;
+
+/*
+ * Dictionary
+ * A map of dictionary entries, indexed by morpheme, with a trie of the same keys.
+ * The map can be read through Deref, eg. dictionary.get("divid"). Entries are
+ * added and removed with insert() and remove(), which keep the trie up to date.
+ */
+#[derive(Debug, Clone)]
+pub struct Dictionary {
+    map: HashMap<String, Entry>,
+    trie: Trie,
+}
+
+impl Dictionary {
+
+    pub fn new() -> Dictionary {
+        return Dictionary { map: HashMap::new(), trie: Trie::new() };
+    }
+
+    /*
+     * insert()
+     *
+     * Params:
+     *    key
+     *    dictionary entry
+     * Return:
+     *    the entry which was replaced, if any
+     */
+    pub fn insert(&mut self, key: String, entry: Entry) -> Option<Entry> {
+        self.trie.insert(&key);
+        return self.map.insert(key, entry);
+    }
+
+    /*
+     * remove()
+     *
+     * Params:
+     *    key
+     * Return:
+     *    the entry which was removed, if any
+     */
+    pub fn remove(&mut self, key: &str) -> Option<Entry> {
+        self.trie.remove(key);
+        return self.map.remove(key);
+    }
+
+    /*
+     * prefixes()
+     *
+     * Finds the keys which are prefixes of a word, eg. 'mis' and 'miskompren'
+     * for 'miskomprenit'.
+     *
+     * Params:
+     *    word
+     * Return:
+     *    list of prefixes, as (length in bytes, length in characters), shortest first
+     */
+    pub fn prefixes(&self, word: &str) -> Vec<(usize, usize)> {
+        return self.trie.prefixes(word);
+    }
+
+    /*
+     * starting_with()
+     *
+     * Finds the keys which begin with a prefix, eg. 'muzik', 'muzikil' and
+     * 'muziknot' for 'muzik'.
+     *
+     * Params:
+     *    prefix
+     * Return:
+     *    list of keys, sorted
+     */
+    pub fn starting_with(&self, prefix: &str) -> Vec<&str> {
+        let mut keys: Vec<&str> = self.trie.starting_with(prefix).iter()
+            .filter_map(|key| self.map.get_key_value(key.as_str()))
+            .map(|(key, _)| key.as_str())
+            .collect();
+        keys.sort();
+        return keys;
+    }
+
+}  // impl Dictionary
+
+impl Default for Dictionary {
+    fn default() -> Dictionary {
+        return Dictionary::new();
+    }
+}
+
+impl Deref for Dictionary {
+    type Target = HashMap<String, Entry>;
+    fn deref(&self) -> &HashMap<String, Entry> {
+        return &self.map;
+    }
+}
+
+impl From<HashMap<String, Entry>> for Dictionary {
+    fn from(map: HashMap<String, Entry>) -> Dictionary {
+        let mut trie = Trie::new();
+        for key in map.keys() { trie.insert(key); }
+        return Dictionary { map, trie };
+    }
+}
+
+
+/*
+ * DictErrorKind
+ * The reason why a row of dictionary data is invalid.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum DictErrorKind {
+    TooFewFields(usize),	// The row has fewer than 9 fields.
+    BadRarity(String),		// The rarity field is not a number.
+    UnknownCode(&'static str, String),	// A field (column name, code) has an unknown code.
+    DuplicateKey(String, usize),	// The key (key, first line) is already defined.
+    BadPrecompiled(usize),	// The precompiled dictionary is invalid at (byte offset).
+}
+
+/*
+ * DictError
+ * An error in the dictionary data.
+ * Fields:
+ *     'line' is the line number of the bad row, counting from 1.
+ *     'kind' is the reason.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct DictError {
+    pub line: usize,
+    pub kind: DictErrorKind,
+}
+
+impl fmt::Display for DictError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            DictErrorKind::TooFewFields(n) =>
+                write!(f, "line {}: expected 9 fields, found {}", self.line, n),
+            DictErrorKind::BadRarity(rarity) =>
+                write!(f, "line {}: invalid rarity '{}'", self.line, rarity),
+            DictErrorKind::UnknownCode(column, code) =>
+                write!(f, "line {}: unknown {} code '{}'", self.line, column, code),
+            DictErrorKind::DuplicateKey(key, first) =>
+                write!(f, "line {}: duplicate key '{}' (first on line {})", self.line, key, first),
+            DictErrorKind::BadPrecompiled(offset) =>
+                write!(f, "precompiled dictionary, byte {}: invalid data", offset),
+        }
+    }
+}
+
+impl Error for DictError {}
+
+
+/*
+ * check_fields
+ *
+ * Checks that a row of dictionary data has enough fields, a numeric rarity,
+ * and known codes. The enum constructors of entry.rs fall back to a default
+ * for unknown codes, so a typo such as 'SUBSTO' would otherwise go unnoticed.
+ *
+ * Params:
+ *    fields of the row
+ *    line number
+ * Return:
+ *    list of errors, empty if the row is valid
+ */
+fn check_fields(fields: &[&str], line_number: usize) -> Vec<DictError> {
+    if fields.len() < 9 {
+        let kind = DictErrorKind::TooFewFields(fields.len());
+        return vec![DictError { line: line_number, kind }];
+    }
+    let mut errors = vec![];
+    if fields[7].parse::<usize>().is_err() {
+        let kind = DictErrorKind::BadRarity(String::from(fields[7]));
+        errors.push(DictError { line: line_number, kind });
+    }
+    let known = [
+        ("part of speech", fields[1], POS::parse(fields[1]).is_some()),
+        ("meaning", fields[2], Meaning::parse(fields[2]).is_some()),
+        ("transitivity", fields[3], Transitivity::parse(fields[3]).is_some()),
+        ("without-ending", fields[4], WithoutEnding::parse(fields[4]).is_some()),
+        ("with-ending", fields[5], WithEnding::parse(fields[5]).is_some()),
+        ("combinability", fields[6], Synthesis::parse(fields[6]).is_some()),
+        ("flag", fields[8], Flag::parse(fields[8]).is_some()),
+    ];
+    for (column, code, ok) in known.iter() {
+        if !ok {
+            let kind = DictErrorKind::UnknownCode(column, String::from(*code));
+            errors.push(DictError { line: line_number, kind });
+        }
+    }
+    return errors;
+}
+
+
+/*
+ * check_row
+ *
+ * Checks a row of dictionary data. See check_fields().
+ *
+ * Params:
+ *    fields of the row
+ *    line number
+ * Return:
+ *    Ok, or the first error
+ */
+fn check_row(fields: &[&str], line_number: usize) -> Result<(), DictError> {
+    if let Some(error) = check_fields(fields, line_number).into_iter().next() {
+        return Err(error);
+    }
+    return Ok(());
+}
+
+
+/*
+ * lint_dictionary
+ *
+ * Validates rows of tab-separated dictionary data, in the format read by
+ * make_dictionary(). Unlike make_dictionary(), it does not stop at the first
+ * bad row, and it also reports duplicate keys. Rows which are excluded
+ * (flag X) or one letter long are not put in the dictionary, so they are
+ * not checked for duplicates.
+ *
+ * Params:
+ *    strings of dictionary data
+ * Return:
+ *    list of errors, in line order
+ */
+pub fn lint_dictionary(data: &str) -> Vec<DictError> {
+
+    let mut errors = vec![];
+    let mut first_lines: HashMap<String, usize> = HashMap::new();
+
+    for (index, line) in data.lines().enumerate() {
+
+        if line.trim().is_empty() { continue; }
+        if line.starts_with('#') { continue; }
+
+        let line_number = index + 1;
+        let split_line: Vec<&str> = line.split_whitespace().collect();
+        errors.append(&mut check_fields(&split_line, line_number));
+        if split_line.len() < 9 { continue; }
+
+        if split_line[8] == "X" { continue; }
+        if x_to_accent(split_line[0]).chars().count() == 1 { continue; }
+        let key = make_key(split_line[0], POS::new(split_line[1]));
+        if let Some(first) = first_lines.get(&key) {
+            let kind = DictErrorKind::DuplicateKey(key, *first);
+            errors.push(DictError { line: line_number, kind });
+        }
+        else {
+            first_lines.insert(key, line_number);
+        }
+    }
+    return errors;
+
+}  // lint_dictionary
+
 
 /*
  * make_dictionary
  *
- * This function takes rows of tab-separated dictionary data and produces a hash map
+ * This function takes rows of tab-separated dictionary data and produces a dictionary
  * which is indexed by morpheme.
  *
  * A typical row of data is:
@@ -31,36 +291,216 @@ use super::entry::*;
  * Params:
  *    strings of dictionary data
  * Return:
- *    hash map of dictionary data
+ *    dictionary, or an error for the first bad row
  */
-pub fn make_dictionary(data: &str) -> HashMap<String, Entry> {
+pub fn make_dictionary(data: &str) -> Result<Dictionary, DictError> {
 
-    let mut esperanto_dictionary: HashMap<String, Entry> = HashMap::new();
+    let mut esperanto_dictionary = Dictionary::new();
 
     // Read the dictionary data into _dict_map.
-    for line in data.lines() {
+    for (index, line) in data.lines().enumerate() {
 
-        if line.len() == 0 { continue; }
+        if line.trim().is_empty() { continue; }
 
         let ch = line.chars().next().unwrap();
         if ch == '#' { continue; }    // Must be a comment line. Skip it.
 
         let split_line: Vec<&str> = line.split_whitespace().collect();
-        // If there are not enough fields, it must be bad data, so skip it.
-        if split_line.len() < 9 {
-            println!(">>>>>>> {}", line);
-            continue;
-        }
+        check_row(&split_line, index + 1)?;
 
         // Make a key
-        let _vorto = String::from(split_line[0]);
-        let _vorto2 = x_to_accent(&_vorto);
-        let _key = _vorto2.replace(".", "").to_lowercase();
+        let _key = make_key(split_line[0], POS::new(split_line[1]));
 
         if let Some(entry) = Entry::new(split_line) {
             esperanto_dictionary.insert(_key.clone(), entry);
         }
     }
-    return esperanto_dictionary;
+    return Ok(esperanto_dictionary);
 
 }  // make_dictionary
+
+
+// The dictionary, precompiled from vortaro.tsv by build.rs.
+static PRECOMPILED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/vortaro.bin"));
+
+/*
+ * Reader
+ * Reads the fields of the precompiled dictionary.
+ */
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+
+    // The error for invalid data at the current position.
+    fn error(&self) -> DictError {
+        return DictError { line: 0, kind: DictErrorKind::BadPrecompiled(self.position) };
+    }
+
+    fn byte(&mut self) -> Result<u8, DictError> {
+        let b = *self.data.get(self.position).ok_or_else(|| self.error())?;
+        self.position += 1;
+        return Ok(b);
+    }
+
+    fn string(&mut self) -> Result<&'a str, DictError> {
+        let length = self.byte()? as usize;
+        let bytes = self.data.get(self.position..self.position + length)
+                             .ok_or_else(|| self.error())?;
+        let s = std::str::from_utf8(bytes).map_err(|_| self.error())?;
+        self.position += length;
+        return Ok(s);
+    }
+}
+
+
+/*
+ * read_precompiled
+ *
+ * Reads a dictionary in the format which build.rs writes. (See build.rs.)
+ *
+ * Params:
+ *    precompiled data
+ * Return:
+ *    dictionary, or an error if the data is invalid
+ */
+fn read_precompiled(data: &[u8]) -> Result<Dictionary, DictError> {
+
+    let mut reader = Reader { data, position: 0 };
+    if !data.starts_with(b"LTRM") || data.get(4) != Some(&1) { return Err(reader.error()); }
+    reader.position = 5;
+
+    let table_size = reader.byte()? as usize;
+    let mut table: Vec<&str> = Vec::with_capacity(table_size);
+    for _ in 0..table_size { table.push(reader.string()?); }
+
+    let count_bytes = [reader.byte()?, reader.byte()?, reader.byte()?, reader.byte()?];
+    let count = u32::from_le_bytes(count_bytes);
+
+    let mut dictionary = Dictionary::new();
+    for _ in 0..count {
+        let key = reader.string()?;
+        let word = reader.string()?;
+        if word.is_empty() { return Err(reader.error()); }
+        let mut codes = [""; 7];
+        for code in codes.iter_mut() {
+            *code = table.get(reader.byte()? as usize).ok_or_else(|| reader.error())?;
+        }
+        let rarity = reader.byte()? as usize;
+        let entry = Entry {
+            word: String::from(word),
+            length: word.chars().count(),
+            capitalization: Capitalization::new(word),
+            part_of_speech: POS::new(codes[0]),
+            meaning: Meaning::new(codes[1]),
+            transitivity: Transitivity::new(codes[2]),
+            without_ending: WithoutEnding::new(codes[3]),
+            with_ending: WithEnding::new(codes[4]),
+            synthesis: Synthesis::new(codes[5]),
+            rarity,
+            flag: Flag::new(codes[6]),
+        };
+        dictionary.insert(String::from(key), entry);
+    }
+    return Ok(dictionary);
+
+}  // read_precompiled
+
+
+/*
+ * load_precompiled
+ *
+ * Loads the dictionary which build.rs precompiled from vortaro.tsv. This is
+ * faster than make_dictionary(), because the rows have already been split,
+ * filtered and converted to accented letters. The keys are the same.
+ *
+ * Return:
+ *    dictionary, or an error if the precompiled data is invalid
+ */
+pub fn load_precompiled() -> Result<Dictionary, DictError> {
+    return read_precompiled(PRECOMPILED);
+}  // load_precompiled
+
+
+/*
+ * add_words
+ *
+ * Adds words from a supplementary word list to the dictionary. Entries in the list
+ * replace entries of the dictionary which have the same key.
+ *
+ * Each line of the list can be a row of tab-separated data, in the same format as
+ * for make_dictionary(), or a single word. A single word is treated as a morpheme
+ * which takes an ending. If the word is divided by periods and ends with a
+ * grammatical ending, eg. 'kamarad.an.o', the ending is removed, and it determines
+ * the part of speech. Otherwise the word is a substantive.
+ *
+ * Params:
+ *    dictionary
+ *    strings of word list data
+ * Return:
+ *    Ok, or an error for the first bad row
+ */
+pub fn add_words(dictionary: &mut Dictionary, data: &str) -> Result<(), DictError> {
+
+    for (index, line) in data.lines().enumerate() {
+
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') { continue; }
+
+        let split_line: Vec<&str> = line.split_whitespace().collect();
+
+        if split_line.len() > 1 {
+            check_row(&split_line, index + 1)?;
+            let key = make_key(split_line[0], POS::new(split_line[1]));
+            if let Some(entry) = Entry::new(split_line) {
+                dictionary.insert(key, entry);
+            }
+            continue;
+        }
+
+        let mut word = split_line[0];
+        let mut part_of_speech = "SUBST";
+        if let Some(period) = word.rfind('.') {
+            let pos = match &word[period + 1..] {
+                "o" => Some("SUBST"),
+                "a" => Some("ADJ"),
+                "e" => Some("ADVERBO"),
+                "i" => Some("VERBO"),
+                _ => None,
+            };
+            if let Some(pos) = pos {
+                part_of_speech = pos;
+                word = &word[..period];
+            }
+        }
+
+        let flag = if word.contains('.') { "K" } else { "R" };
+        let fields = vec![word, part_of_speech, "N", "N", "N", "KF", "NLM", "2", flag];
+        let key = make_key(word, POS::new(part_of_speech));
+        if let Some(entry) = Entry::new(fields) {
+            dictionary.insert(key, entry);
+        }
+    }
+    return Ok(());
+
+}  // add_words
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn precompiled_data() {
+        assert!(read_precompiled(PRECOMPILED).is_ok());
+        let truncated = &PRECOMPILED[..PRECOMPILED.len() - 3];
+        let error = read_precompiled(truncated).unwrap_err();
+        assert_eq!(error.kind, DictErrorKind::BadPrecompiled(PRECOMPILED.len() - 3));
+        let error = read_precompiled(b"LTRM\x02").unwrap_err();
+        assert_eq!(error.kind, DictErrorKind::BadPrecompiled(0));
+    }
+
+}  // tests
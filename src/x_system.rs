@@ -0,0 +1,59 @@
+// x_system - Converts the x-system (cx, gx, hx, jx, sx, ux) to accented letters.
+// This module is shared with build.rs, which converts the dictionary when the
+// library is built, so that the keys are made in the same way.
+// Klivo 2020-05-30
+
+/*
+ * x_to_accent()
+ *
+ * Converts cx to ĉ, sx to ŝ, etc., for an entire string.
+ *
+ * A doubled x after a letter which accepts a hat is an escape for a literal x.
+ * For example, 'sxx' becomes 'sx', whereas 'sx' becomes 'ŝ'. At the end of a word,
+ * a single x is still converted ('aux' -> 'aŭ'), and a doubled x is still a
+ * literal x ('auxx' -> 'aux'). A tripled x is an escape followed by a plain x
+ * ('auxxx' -> 'auxx').
+ *
+ * Params:
+ *    original word (&str)
+ * Return:
+ *    new word (String)
+ */
+pub fn x_to_accent(word: &str) -> String {
+
+    let word = word.chars().collect::<Vec<_>>();
+    let length = word.len();
+    let mut _new_word = String::from("");
+
+    let mut skip_x = 0;   // For skipping over x.
+    for i in 0..length {
+        if skip_x > 0 { skip_x -= 1; continue; }
+        let ch1 = word[i];
+        if accepts_hat!(ch1) {
+            if i < (length - 1) {
+                let ch2 = word[i + 1];
+                if is_x!(ch2) && i < (length - 2) && is_x!(word[i + 2]) {
+                    // Escaped x. Keep the letter and one x.
+                    _new_word.push(ch1);
+                    _new_word.push(ch2);
+                    skip_x = 2;
+                }
+                else if is_x!(ch2) {
+                    _new_word.push(accent_letter!(ch1));
+                    skip_x = 1;
+                }
+                else {
+                    _new_word.push(ch1);
+                }
+            }
+            else {
+                _new_word.push(ch1);
+            }
+        }
+        else {
+            _new_word.push(ch1);
+        }
+    }  // for
+
+    return _new_word;
+}  // x_to_accent